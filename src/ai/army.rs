@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+use bevy_rts_pathfinding::components as pf_comps;
+
+use crate::{
+    combat::components::{AttackOrder, Health, Target},
+    structures::components::Structure,
+    teams::Team,
+    units::components::{Deploying, MoveTo, PathTo, Unit, UnitType},
+};
+
+use super::components::AiPlayer;
+
+// once enough units are idle, they all go after the same target together
+pub(super) fn launch_attacks(
+    mut cmds: Commands,
+    q_ai: Query<&AiPlayer>,
+    q_idle: Query<
+        (Entity, &Team, &UnitType),
+        (
+            With<Unit>,
+            Without<AttackOrder>,
+            Without<MoveTo>,
+            Without<pf_comps::Destination>,
+            Without<Deploying>,
+        ),
+    >,
+    q_target: Query<(Entity, &Transform, &Team, &Health, Has<Structure>)>,
+) {
+    for ai in q_ai.iter() {
+        if !ai.is_thinking() {
+            continue;
+        }
+
+        let Some(group_size) = ai.difficulty.attack_group_size() else {
+            continue;
+        };
+
+        let group: Vec<Entity> = q_idle
+            .iter()
+            .filter(|(_, team, unit_type)| **team == ai.team && unit_type.is_armed())
+            .map(|(ent, _, _)| ent)
+            .collect();
+
+        if group.len() < group_size {
+            continue;
+        }
+
+        // the ai isn't bound by the fog of war. it goes for the closest structure first, so
+        // attacks hit the enemy base instead of chasing units around the map
+        let target = q_target
+            .iter()
+            .filter(|(_, _, team, health, _)| ai.team.is_enemy(team) && !health.is_dead())
+            .min_by(|(_, a, _, _, a_structure), (_, b, _, _, b_structure)| {
+                let a_dist = a.translation.distance_squared(ai.base);
+                let b_dist = b.translation.distance_squared(ai.base);
+                b_structure.cmp(a_structure).then(a_dist.total_cmp(&b_dist))
+            })
+            .map(|(ent, trans, _, _, _)| (ent, trans.translation));
+
+        let Some((target, target_pos)) = target else {
+            continue;
+        };
+
+        // the group paths to the target together, and closes in on it from there
+        for ent in group {
+            cmds.entity(ent).insert((
+                AttackOrder(target),
+                Target(target),
+                pf_comps::Destination,
+                PathTo(target_pos),
+            ));
+        }
+    }
+}
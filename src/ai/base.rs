@@ -0,0 +1,235 @@
+use bevy::prelude::*;
+use bevy_rapier3d::plugin::{RapierContext, ReadRapierContext};
+
+use crate::{
+    asset_manager::models::MyModels,
+    harvesting::components::ResourceField,
+    structures::{
+        components::{BuildQueue, Structure, StructureType, UnderConstruction},
+        resources::{PowerGrid, PowerGrids},
+    },
+    teams::Team,
+    units::{
+        components::{Unit, UnitType},
+        events::{QueueSolderEv, QueueVehicleEv},
+    },
+    utils, MAP_DEPTH, MAP_WIDTH,
+};
+
+use super::components::AiPlayer;
+
+// structures the ai works through, in order. extra power plants are added whenever power runs low
+const BUILD_ORDER: [StructureType; 9] = [
+    StructureType::PowerPlant,
+    StructureType::Refinery,
+    StructureType::Barracks,
+    StructureType::VehicleDepot,
+    StructureType::PowerPlant,
+    StructureType::Cannon,
+    StructureType::Refinery,
+    StructureType::Cannon,
+    StructureType::Cannon,
+];
+const MAX_QUEUED: usize = 2; // per production structure type
+const BUILD_RING_STEP: f32 = 50.0;
+const BUILD_RINGS: i32 = 6;
+const BUILD_SPACING: f32 = 10.0;
+const FIELD_CLEARANCE: f32 = 35.0; // keep room around resource fields for the harvesters
+
+// places the next structure of the build order once the previous one is finished
+pub(super) fn build_base(
+    mut cmds: Commands,
+    mut q_ai: Query<&mut AiPlayer>,
+    q_structure: Query<(&StructureType, &Team, Has<UnderConstruction>), With<Structure>>,
+    q_field: Query<&Transform, With<ResourceField>>,
+    read_rapier: ReadRapierContext,
+    power_grids: Res<PowerGrids>,
+    my_models: Res<MyModels>,
+) {
+    let Ok(rapier_ctx) = read_rapier.single() else {
+        return;
+    };
+
+    let fields: Vec<Vec3> = q_field.iter().map(|trans| trans.translation).collect();
+
+    for mut ai in q_ai.iter_mut() {
+        if !ai.is_thinking() {
+            continue;
+        }
+
+        let owned: Vec<(StructureType, bool)> = q_structure
+            .iter()
+            .filter(|(_, team, _)| **team == ai.team)
+            .map(|(structure, _, under_construction)| (*structure, under_construction))
+            .collect();
+
+        // one structure at a time
+        if owned
+            .iter()
+            .any(|(_, under_construction)| *under_construction)
+        {
+            continue;
+        }
+
+        let Some(structure) = next_structure(&owned, &power_grids.get(&ai.team)) else {
+            ai.saving_for = 0;
+            continue;
+        };
+
+        ai.saving_for = structure.cost();
+        if ai.funds < structure.cost() {
+            continue;
+        }
+
+        let (mut scene, collider, mut rb, _, _, _, size) = structure.build_placeholder(&my_models);
+        let Some(pos) = find_build_site(&rapier_ctx, ai.base, size.0, &fields) else {
+            continue;
+        };
+
+        ai.spend(structure.cost());
+        ai.saving_for = 0;
+
+        let structure_ent = cmds
+            .spawn((collider, size, Transform::from_translation(pos), structure))
+            .id();
+
+        structure.place(
+            structure_ent,
+            ai.team,
+            &my_models,
+            &mut scene,
+            &mut rb,
+            &mut cmds,
+        );
+        cmds.entity(structure_ent).insert((scene, rb));
+    }
+}
+
+fn next_structure(owned: &[(StructureType, bool)], grid: &PowerGrid) -> Option<StructureType> {
+    let count = |structure: StructureType| owned.iter().filter(|(s, _)| *s == structure).count();
+
+    let mut needed = Vec::new();
+    let next = BUILD_ORDER.iter().copied().find(|structure| {
+        needed.push(*structure);
+        count(*structure) < needed.iter().filter(|s| *s == structure).count()
+    })?;
+
+    // don't let the next structure take the grid into low power
+    if next.power() < 0 && grid.produced < grid.consumed - next.power() {
+        return Some(StructureType::PowerPlant);
+    }
+
+    Some(next)
+}
+
+// searches rings around the base for a spot that is clear of the map edge, fields and anything solid
+fn find_build_site(
+    rapier_ctx: &RapierContext,
+    base: Vec3,
+    size: Vec3,
+    fields: &[Vec3],
+) -> Option<Vec3> {
+    let radius = size.xz().length() / 2.0 + BUILD_SPACING;
+    let bounds = Vec2::new(MAP_WIDTH, MAP_DEPTH) / 2.0 - size.xz() / 2.0;
+
+    for ring in 1..=BUILD_RINGS {
+        let spots = ring * 6;
+        for i in 0..spots {
+            let angle = i as f32 * std::f32::consts::TAU / spots as f32;
+            let pos =
+                base + Vec3::new(angle.cos(), 0.0, angle.sin()) * ring as f32 * BUILD_RING_STEP;
+
+            if pos.x.abs() > bounds.x || pos.z.abs() > bounds.y {
+                continue;
+            }
+
+            if fields
+                .iter()
+                .any(|field| field.distance(pos) < radius + FIELD_CLEARANCE)
+            {
+                continue;
+            }
+
+            let center = pos + Vec3::Y * size.y / 2.0;
+            if utils::overlap_sphere(rapier_ctx, center, radius).is_empty() {
+                return Some(center);
+            }
+        }
+    }
+
+    None
+}
+
+// keeps the harvesters topped up, and spends whatever the build order doesn't need on an army
+pub(super) fn queue_units(
+    mut cmds: Commands,
+    mut q_ai: Query<&mut AiPlayer>,
+    q_structure: Query<
+        (&StructureType, &Team, Option<&BuildQueue>),
+        (With<Structure>, Without<UnderConstruction>),
+    >,
+    q_unit: Query<(&UnitType, &Team), With<Unit>>,
+) {
+    for mut ai in q_ai.iter_mut() {
+        if !ai.is_thinking() {
+            continue;
+        }
+
+        let team = ai.team;
+        let structures = || q_structure.iter().filter(move |(_, t, _)| **t == team);
+        let units = || q_unit.iter().filter(move |(_, t)| **t == team);
+
+        let refineries = structures()
+            .filter(|(structure, _, _)| **structure == StructureType::Refinery)
+            .count();
+
+        // what's already queued counts towards the army and the harvesters
+        let queued = |source: StructureType| -> Vec<UnitType> {
+            structures()
+                .filter(|(structure, _, _)| **structure == source)
+                .filter_map(|(_, _, build_queue)| build_queue)
+                .flat_map(|build_queue| build_queue.0.iter().map(|(unit, _)| *unit))
+                .collect()
+        };
+        let has = |source: StructureType| structures().any(|(s, _, _)| *s == source);
+        let count = |unit: UnitType, queued: &[UnitType]| {
+            units().filter(|(u, _)| **u == unit).count()
+                + queued.iter().filter(|u| **u == unit).count()
+        };
+
+        if has(StructureType::VehicleDepot) {
+            let queued_vehicles = queued(StructureType::VehicleDepot);
+            let harvesters = count(UnitType::Harvester, &queued_vehicles);
+            let tanks = count(UnitType::TankGen1, &queued_vehicles);
+            let artillery = count(UnitType::Artillery, &queued_vehicles);
+
+            let unit = if harvesters < refineries * ai.difficulty.harvesters_per_refinery() {
+                UnitType::Harvester
+            } else if artillery * 3 < tanks {
+                UnitType::Artillery
+            } else {
+                UnitType::TankGen1
+            };
+
+            // harvesters pay for themselves, so they don't wait on the build order
+            let reserve = if unit == UnitType::Harvester {
+                0
+            } else {
+                ai.saving_for
+            };
+            if queued_vehicles.len() < MAX_QUEUED
+                && ai.funds - reserve >= unit.cost()
+                && ai.spend(unit.cost())
+            {
+                cmds.trigger(QueueVehicleEv { unit, team });
+            }
+        }
+
+        if has(StructureType::Barracks) && queued(StructureType::Barracks).len() < MAX_QUEUED {
+            let unit = UnitType::Rifleman;
+            if ai.funds - ai.saving_for >= unit.cost() && ai.spend(unit.cost()) {
+                cmds.trigger(QueueSolderEv { unit, team });
+            }
+        }
+    }
+}
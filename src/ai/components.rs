@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::{bank::Bank, teams::Team};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AiDifficulty {
+    // follows the build order and defends, but never attacks. a predictable opponent for testing
+    Scripted,
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl AiDifficulty {
+    // parses the '-easy', '-normal', '-hard' and '-scripted' launch arguments
+    pub fn from_arg(arg: &str) -> Option<Self> {
+        match arg {
+            "-scripted" => Some(AiDifficulty::Scripted),
+            "-easy" => Some(AiDifficulty::Easy),
+            "-normal" => Some(AiDifficulty::Normal),
+            "-hard" => Some(AiDifficulty::Hard),
+            _ => None,
+        }
+    }
+
+    // time between decisions
+    pub fn think_interval(&self) -> Duration {
+        match self {
+            AiDifficulty::Scripted => Duration::from_secs(2),
+            AiDifficulty::Easy => Duration::from_secs(4),
+            AiDifficulty::Normal => Duration::from_secs(2),
+            AiDifficulty::Hard => Duration::from_secs(1),
+        }
+    }
+
+    // multiplier on everything the ai's harvesters bring in
+    pub fn income(&self) -> f32 {
+        match self {
+            AiDifficulty::Scripted => 1.0,
+            AiDifficulty::Easy => 0.75,
+            AiDifficulty::Normal => 1.0,
+            AiDifficulty::Hard => 1.25,
+        }
+    }
+
+    // idle units gathered before an attack goes out
+    pub fn attack_group_size(&self) -> Option<usize> {
+        match self {
+            AiDifficulty::Scripted => None,
+            AiDifficulty::Easy => Some(4),
+            AiDifficulty::Normal => Some(6),
+            AiDifficulty::Hard => Some(10),
+        }
+    }
+
+    pub fn harvesters_per_refinery(&self) -> usize {
+        match self {
+            AiDifficulty::Easy => 1,
+            _ => 2,
+        }
+    }
+}
+
+// a computer controlled team. it pays for everything out of its own funds, not the bank
+#[derive(Component, Debug)]
+pub struct AiPlayer {
+    pub team: Team,
+    pub difficulty: AiDifficulty,
+    pub funds: i32,
+    pub base: Vec3,
+    pub think_timer: Timer,
+    // what the next structure costs, units are only bought with what's left over
+    pub saving_for: i32,
+}
+
+impl AiPlayer {
+    pub fn new(team: Team, difficulty: AiDifficulty, base: Vec3) -> Self {
+        Self {
+            team,
+            difficulty,
+            funds: Bank::default().funds,
+            base,
+            think_timer: Timer::new(difficulty.think_interval(), TimerMode::Repeating),
+            saving_for: 0,
+        }
+    }
+
+    pub fn is_thinking(&self) -> bool {
+        self.think_timer.just_finished()
+    }
+
+    // pays for something if there is enough money for it
+    pub fn spend(&mut self, amount: i32) -> bool {
+        if self.funds < amount {
+            return false;
+        }
+
+        self.funds -= amount;
+        true
+    }
+}
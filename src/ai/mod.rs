@@ -0,0 +1,118 @@
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_timer;
+use std::time::Duration;
+
+mod army;
+mod base;
+pub mod components;
+
+use components::{AiDifficulty, AiPlayer};
+
+use crate::{
+    bank::DepositFundsEv,
+    game_state::{GameState, MatchResult},
+    structures::components::Structure,
+    teams::Team,
+    units::components::Unit,
+    MAP_DEPTH, MAP_WIDTH,
+};
+
+const HEADLESS_MATCH_SECS: f32 = 1200.0;
+
+// computer controlled opponents. in headless mode the local team is handed to a scripted ai too
+pub struct AiPlugin {
+    pub difficulty: AiDifficulty,
+    pub headless: bool,
+}
+
+impl Plugin for AiPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(AiSettings {
+            difficulty: self.difficulty,
+            headless: self.headless,
+        })
+        .add_systems(Startup, spawn_ai_players)
+        .add_systems(
+            Update,
+            (
+                tick_ai_players,
+                (base::build_base, base::queue_units, army::launch_attacks),
+            )
+                .chain()
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_observer(obs_ai_income);
+
+        if self.headless {
+            app.add_systems(
+                Update,
+                report_headless_match.run_if(on_timer(Duration::from_secs(30))),
+            )
+            .add_systems(OnEnter(GameState::GameOver), end_headless_match);
+        }
+    }
+}
+
+#[derive(Resource)]
+struct AiSettings {
+    difficulty: AiDifficulty,
+    headless: bool,
+}
+
+// each side gets the corner with a resource field to itself
+fn spawn_ai_players(mut cmds: Commands, settings: Res<AiSettings>) {
+    let (x, z) = (MAP_WIDTH / 3.0, MAP_DEPTH / 3.0);
+
+    cmds.spawn((
+        AiPlayer::new(Team::Enemy, settings.difficulty, Vec3::new(x, 0.0, -z)),
+        Name::new("AI Player"),
+    ));
+
+    if settings.headless {
+        cmds.spawn((
+            AiPlayer::new(Team::local(), AiDifficulty::Scripted, Vec3::new(-x, 0.0, z)),
+            Name::new("Scripted Player"),
+        ));
+    }
+}
+
+fn tick_ai_players(mut q_ai: Query<&mut AiPlayer>, time: Res<Time>) {
+    for mut ai in q_ai.iter_mut() {
+        ai.think_timer.tick(time.delta());
+    }
+}
+
+fn obs_ai_income(trigger: Trigger<DepositFundsEv>, mut q_ai: Query<&mut AiPlayer>) {
+    for mut ai in q_ai.iter_mut().filter(|ai| ai.team == trigger.team) {
+        ai.funds += (trigger.amount as f32 * ai.difficulty.income()) as i32;
+    }
+}
+
+// nobody is watching a headless match, so log how it's going and give up once it drags on
+fn report_headless_match(
+    time: Res<Time>,
+    q_ai: Query<&AiPlayer>,
+    q_structure: Query<&Team, With<Structure>>,
+    q_unit: Query<&Team, With<Unit>>,
+    mut app_exit: EventWriter<AppExit>,
+) {
+    for ai in q_ai.iter() {
+        let structures = q_structure.iter().filter(|team| **team == ai.team).count();
+        let units = q_unit.iter().filter(|team| **team == ai.team).count();
+
+        info!(
+            "{:?} ({:?}): ${}, {} structures, {} units",
+            ai.team, ai.difficulty, ai.funds, structures, units
+        );
+    }
+
+    if time.elapsed_secs() >= HEADLESS_MATCH_SECS {
+        info!("match ran out of time");
+        app_exit.write(AppExit::Success);
+    }
+}
+
+fn end_headless_match(result: Res<MatchResult>, mut app_exit: EventWriter<AppExit>) {
+    info!("match over, {:?}: {:?}", Team::local(), *result);
+    app_exit.write(AppExit::Success);
+}
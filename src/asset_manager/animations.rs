@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use std::time::Duration;
 
-use crate::structures::{components::PrimaryVehicleDepot, events::BuildVehicleEv};
+use crate::structures::events::BuildVehicleEv;
 
 pub struct AnimtationsPlugin;
 
@@ -45,16 +45,14 @@ fn build_animation_graph(
 }
 
 fn garage_door_animation(
-    _trigger: Trigger<BuildVehicleEv>,
+    trigger: Trigger<BuildVehicleEv>,
     mut cmds: Commands,
     animations: Res<Animations>,
-    q_vehicle_depot: Query<Entity, With<PrimaryVehicleDepot>>,
     q_children: Query<&Children>,
     mut players: Query<(Entity, &mut AnimationPlayer)>,
 ) {
-    let Ok(vehicle_depot_ent) = q_vehicle_depot.single() else {
-        return;
-    };
+    // open the door of the depot that built the vehicle
+    let vehicle_depot_ent = trigger.structure;
 
     for child_ent in q_children.iter_descendants(vehicle_depot_ent) {
         if let Ok((ent, mut animation_player)) = players.get_mut(child_ent) {
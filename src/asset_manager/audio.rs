@@ -36,7 +36,7 @@ impl UnitAudioEv {
     }
 }
 
-#[derive(Debug, EnumString, Hash, PartialEq, Eq)]
+#[derive(Debug, EnumString, Hash, PartialEq, Eq, Clone, Copy)]
 pub enum AudioCmd {
     #[strum(serialize = "move")]
     Relocate,
@@ -44,6 +44,16 @@ pub enum AudioCmd {
     Select,
     #[strum(serialize = "ready")]
     Ready,
+    #[strum(serialize = "stop")]
+    Stop,
+    #[strum(serialize = "hold")]
+    Hold,
+    #[strum(serialize = "patrol")]
+    Patrol,
+    #[strum(serialize = "guard")]
+    Guard,
+    #[strum(serialize = "attack_move")]
+    AttackMove,
 }
 
 #[derive(Resource, Default)]
@@ -163,6 +173,19 @@ fn unit_audio(trigger: Trigger<UnitAudioEv>, mut cmds: Commands, my_audio: Res<M
     }
 }
 
+// the unit type most of the group is made up of, which does the talking for all of them
+pub fn most_common_unit<'a>(units: impl Iterator<Item = &'a UnitType>) -> Option<UnitType> {
+    let mut counts: HashMap<UnitType, u32> = HashMap::new();
+    for unit_type in units {
+        *counts.entry(*unit_type).or_insert(0) += 1;
+    }
+
+    counts
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(unit_type, _)| unit_type)
+}
+
 fn multiple_select(
     _trigger: Trigger<SelectMultipleUnitEv>,
     mut cmds: Commands,
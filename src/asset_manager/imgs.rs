@@ -15,6 +15,7 @@ pub struct MyImgs {
     pub cursor_relocate: Handle<Image>,
     pub cursor_select: Handle<Image>,
     pub cursor_standard: Handle<Image>,
+    pub cursor_attack_move: Handle<Image>,
     pub cmd_intrfce_structures: Handle<Image>,
     pub cmd_intrfce_units: Handle<Image>,
     pub cmd_intrfce_mini_map: Handle<Image>,
@@ -30,10 +31,13 @@ pub struct MyImgs {
     pub structure_vehicle_depot: Handle<Image>,
     pub structure_research_center: Handle<Image>,
     pub structure_satellite_dish: Handle<Image>,
+    pub structure_refinery: Handle<Image>,
+    pub structure_power_plant: Handle<Image>,
     pub unit_tank_gen_1: Handle<Image>,
     pub unit_tank_gen_2: Handle<Image>,
     pub unit_artillery: Handle<Image>,
     pub unit_rifleman: Handle<Image>,
+    pub unit_harvester: Handle<Image>,
 }
 
 fn load_imgs(mut my_imgs: ResMut<MyImgs>, assets: Res<AssetServer>) {
@@ -42,6 +46,7 @@ fn load_imgs(mut my_imgs: ResMut<MyImgs>, assets: Res<AssetServer>) {
     my_imgs.cursor_relocate = assets.load("imgs/cursor/relocate.png");
     my_imgs.cursor_select = assets.load("imgs/cursor/select.png");
     my_imgs.cursor_standard = assets.load("imgs/cursor/standard.png");
+    my_imgs.cursor_attack_move = assets.load("imgs/cursor/attack_move.png");
 
     my_imgs.cmd_intrfce_structures = assets.load("imgs/cmd_cntr_structures.png");
     my_imgs.cmd_intrfce_units = assets.load("imgs/cmd_cntr_units.png");
@@ -55,11 +60,14 @@ fn load_imgs(mut my_imgs: ResMut<MyImgs>, assets: Res<AssetServer>) {
     my_imgs.structure_vehicle_depot = assets.load("imgs/structures/vehicle_depot.png");
     my_imgs.structure_research_center = assets.load("imgs/structures/research_center.png");
     my_imgs.structure_satellite_dish = assets.load("imgs/structures/satellite_dish.png");
+    my_imgs.structure_refinery = assets.load("imgs/structures/research_center.png"); // TODO: Temporary
+    my_imgs.structure_power_plant = assets.load("imgs/structures/satellite_dish.png"); // TODO: Temporary
 
     my_imgs.unit_tank_gen_1 = assets.load("imgs/units/tank_gen_1.png");
     my_imgs.unit_tank_gen_2 = assets.load("imgs/units/tank_gen_2.png");
     my_imgs.unit_artillery = assets.load("imgs/units/artillery.png");
     my_imgs.unit_rifleman = assets.load("imgs/units/rifleman.png");
+    my_imgs.unit_harvester = assets.load("imgs/units/tank_gen_1.png"); // TODO: Temporary
 
     my_imgs.info_ctr = assets.load("imgs/cmd_interface/info_ctr.png");
     my_imgs.info_ctr_dmg = assets.load("imgs/info_ctr/dmg.png");
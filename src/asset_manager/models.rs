@@ -20,6 +20,9 @@ pub struct MyModels {
     pub vehicle_depot: Handle<Scene>,
     pub research_center: Handle<Scene>,
     pub satellite_dish: Handle<Scene>,
+    pub harvester: Handle<Scene>,
+    pub refinery: Handle<Scene>,
+    pub power_plant: Handle<Scene>,
     pub placeholders: Placeholders,
 }
 
@@ -35,6 +38,10 @@ pub struct Placeholders {
     pub research_center_invalid: Handle<Scene>,
     pub satellite_dish_valid: Handle<Scene>,
     pub satellite_dish_invalid: Handle<Scene>,
+    pub refinery_valid: Handle<Scene>,
+    pub refinery_invalid: Handle<Scene>,
+    pub power_plant_valid: Handle<Scene>,
+    pub power_plant_invalid: Handle<Scene>,
 }
 
 fn load_models(mut my_models: ResMut<MyModels>, assets: Res<AssetServer>) {
@@ -43,6 +50,7 @@ fn load_models(mut my_models: ResMut<MyModels>, assets: Res<AssetServer>) {
     my_models.tank_gen1 = assets.load("models/units/tank_gen_1/tank_gen_1.gltf#Scene0");
     my_models.tank_gen2 = assets.load("models/units/tank_gen_2/tank_gen_2.gltf#Scene0");
     my_models.artillery = assets.load("models/units/artillery/artillery.gltf#Scene0");
+    my_models.harvester = assets.load("models/units/tank_gen_1/tank_gen_1.gltf#Scene0"); // TODO: Temporary
 
     // structures
     my_models.barracks = assets.load("models/structures/barracks.gltf#Scene0");
@@ -51,6 +59,8 @@ fn load_models(mut my_models: ResMut<MyModels>, assets: Res<AssetServer>) {
         assets.load("models/structures/vehicle_depot/vehicle_depot.gltf#Scene0");
     my_models.research_center = assets.load("models/structures/research_center.gltf#Scene0");
     my_models.satellite_dish = assets.load("models/structures/satellite_dish.gltf#Scene0");
+    my_models.refinery = assets.load("models/structures/research_center.gltf#Scene0"); // TODO: Temporary
+    my_models.power_plant = assets.load("models/structures/satellite_dish.gltf#Scene0"); // TODO: Temporary
 
     // structure placeholders valid
     my_models.placeholders.barracks_valid =
@@ -63,6 +73,10 @@ fn load_models(mut my_models: ResMut<MyModels>, assets: Res<AssetServer>) {
         assets.load("models/structures/placeholders/valid/research_center.gltf#Scene0");
     my_models.placeholders.satellite_dish_valid =
         assets.load("models/structures/placeholders/valid/satellite_dish.gltf#Scene0");
+    my_models.placeholders.refinery_valid =
+        assets.load("models/structures/placeholders/valid/research_center.gltf#Scene0"); // TODO: Temporary
+    my_models.placeholders.power_plant_valid =
+        assets.load("models/structures/placeholders/valid/satellite_dish.gltf#Scene0"); // TODO: Temporary
 
     // structure placeholders invalid
     my_models.placeholders.barracks_invalid =
@@ -75,4 +89,8 @@ fn load_models(mut my_models: ResMut<MyModels>, assets: Res<AssetServer>) {
         assets.load("models/structures/placeholders/invalid/research_center.gltf#Scene0");
     my_models.placeholders.satellite_dish_invalid =
         assets.load("models/structures/placeholders/invalid/satellite_dish.gltf#Scene0");
+    my_models.placeholders.refinery_invalid =
+        assets.load("models/structures/placeholders/invalid/research_center.gltf#Scene0"); // TODO: Temporary
+    my_models.placeholders.power_plant_invalid =
+        assets.load("models/structures/placeholders/invalid/satellite_dish.gltf#Scene0"); // TODO: Temporary
 }
@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 
 use crate::cmd_interface::components::BankTxt;
+use crate::teams::Team;
 
 pub struct BankPlugin;
 
@@ -8,23 +9,32 @@ impl Plugin for BankPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<Bank>()
             .add_systems(Update, update_bank_funds)
-            .add_observer(adjust_funds);
+            .add_observer(adjust_funds)
+            .add_observer(deposit_funds);
     }
 }
 
 #[derive(Event)]
 pub struct AdjustFundsEv(pub i32);
 
+// income earned by a team, the ai keeps track of its own funds
+#[derive(Event)]
+pub struct DepositFundsEv {
+    pub team: Team,
+    pub amount: i32,
+}
+
 #[derive(Resource)]
 pub struct Bank {
     pub funds: i32,
     pub displayed_funds: i32,
 }
 
+// enough to get a refinery going, everything after that has to be harvested
 impl Default for Bank {
     fn default() -> Self {
         Self {
-            funds: 10000,
+            funds: 5000,
             displayed_funds: 0,
         }
     }
@@ -35,6 +45,13 @@ fn adjust_funds(trigger: Trigger<AdjustFundsEv>, mut bank: ResMut<Bank>) {
     bank.funds += adjustment;
 }
 
+// the bank only holds the local team's funds
+fn deposit_funds(trigger: Trigger<DepositFundsEv>, mut bank: ResMut<Bank>) {
+    if trigger.team.is_local() {
+        bank.funds += trigger.amount;
+    }
+}
+
 fn update_bank_funds(
     time: Res<Time>,
     mut bank: ResMut<Bank>,
@@ -55,10 +55,10 @@ fn spawn_camera(mut cmds: Commands) {
         },
         RtsCameraControls {
             edge_pan_width: 0.01,
-            key_left: KeyCode::KeyA,
-            key_right: KeyCode::KeyD,
-            key_up: KeyCode::KeyW,
-            key_down: KeyCode::KeyS,
+            key_left: KeyCode::ArrowLeft,
+            key_right: KeyCode::ArrowRight,
+            key_up: KeyCode::ArrowUp,
+            key_down: KeyCode::ArrowDown,
             pan_speed: 165.0,
             zoom_sensitivity: 0.2,
             ..default()
@@ -7,9 +7,13 @@ use super::resources::InfoContainerData;
 use crate::asset_manager::models::MyModels;
 use crate::bank::AdjustFundsEv;
 use crate::bank::Bank;
-use crate::events::DeselectAllUnitsEv;
+use crate::events::{DeselectAllUnitsEv, SelectUnitsEv, UnitOrderEv};
+use crate::health_bars::HealthBars;
+use crate::research::events::{CancelResearchEv, QueueResearchEv};
 use crate::resources::*;
 use crate::structures::components::*;
+use crate::units::components::{SelectedUnit, UnitType};
+use crate::units::resources::Formation;
 
 pub const CLR_STRUCTURE_BUILD_ACTIONS: Color = Color::srgb(0.87, 0.87, 1.0);
 const CLR_STRUCTURE_BUILD_ACTIONS_HVR: Color = Color::srgb(1.0, 1.0, 1.0);
@@ -26,7 +30,13 @@ impl Plugin for BuildActionsPlugin {
                 reset_info_ctr_hvr_state,
                 build_structure_btn_interaction.after(reset_info_ctr_hvr_state),
                 build_unit_btn_interaction.after(reset_info_ctr_hvr_state),
+                research_btn_interaction.after(reset_info_ctr_hvr_state),
                 toggle_info_ctr,
+                order_btn_interaction,
+                formation_btn_interaction,
+                health_bars_btn_interaction,
+                selection_portrait_interaction,
+                toggle_orders_ctr,
             ),
         )
         .add_observer(select_structure);
@@ -36,7 +46,19 @@ impl Plugin for BuildActionsPlugin {
 fn cmd_interface_interaction(
     mut game_cmds: ResMut<GameCommands>,
     q_p: Query<&Interaction, With<CmdInterfaceCtr>>,
-    q_c: Query<&Interaction, Or<(With<StructureType>, With<UnitCtr>)>>,
+    q_c: Query<
+        &Interaction,
+        Or<(
+            With<StructureType>,
+            With<UnitCtr>,
+            With<ResearchCtr>,
+            With<OrderBtn>,
+            With<FormationBtn>,
+            With<HealthBarsBtn>,
+            With<SelectionPanel>,
+            With<SelectionPortrait>,
+        )>,
+    >,
 ) {
     let hvr_parent = q_p.iter().any(|intrct| *intrct == Interaction::Hovered);
     let hvr_child = q_c.iter().any(|intrct| *intrct == Interaction::Hovered);
@@ -76,15 +98,115 @@ fn build_structure_btn_interaction(
                 info_ctr_data.name = structure.to_string();
                 info_ctr_data.cost = structure.cost();
                 info_ctr_data.build_time = structure.build_time();
-                info_ctr_data.hp = None;
-                info_ctr_data.dmg = None;
+                info_ctr_data.hp = Some(structure.hp());
+                info_ctr_data.dmg = structure.dmg();
                 info_ctr_data.speed = None;
+                info_ctr_data.range = structure.range();
+                info_ctr_data.rate_of_fire = structure.rate_of_fire();
                 img.color = CLR_STRUCTURE_BUILD_ACTIONS_HVR;
+
+                if input.just_pressed(MouseButton::Right) {
+                    cmds.trigger(CancelStructureEv(*structure));
+                }
             }
         }
     }
 }
 
+fn order_btn_interaction(
+    mut cmds: Commands,
+    mut q_btn: Query<(&Interaction, &mut BorderColor, &OrderBtn), Changed<Interaction>>,
+) {
+    for (interaction, mut border_clr, order_btn) in q_btn.iter_mut() {
+        match interaction {
+            Interaction::None => border_clr.0 = CLR_STRUCTURE_BUILD_ACTIONS,
+            Interaction::Hovered => border_clr.0 = CLR_STRUCTURE_BUILD_ACTIONS_HVR,
+            Interaction::Pressed => cmds.trigger(UnitOrderEv(order_btn.0)),
+        }
+    }
+}
+
+fn formation_btn_interaction(
+    mut formation: ResMut<Formation>,
+    mut q_btn: Query<(&Interaction, &mut BorderColor), (With<FormationBtn>, Changed<Interaction>)>,
+) {
+    for (interaction, mut border_clr) in q_btn.iter_mut() {
+        match interaction {
+            Interaction::None => border_clr.0 = CLR_STRUCTURE_BUILD_ACTIONS,
+            Interaction::Hovered => border_clr.0 = CLR_STRUCTURE_BUILD_ACTIONS_HVR,
+            Interaction::Pressed => *formation = formation.next(),
+        }
+    }
+}
+
+fn health_bars_btn_interaction(
+    mut health_bars: ResMut<HealthBars>,
+    mut q_btn: Query<(&Interaction, &mut BorderColor), (With<HealthBarsBtn>, Changed<Interaction>)>,
+) {
+    for (interaction, mut border_clr) in q_btn.iter_mut() {
+        match interaction {
+            Interaction::None => border_clr.0 = CLR_STRUCTURE_BUILD_ACTIONS,
+            Interaction::Hovered => border_clr.0 = CLR_STRUCTURE_BUILD_ACTIONS_HVR,
+            Interaction::Pressed => *health_bars = health_bars.next(),
+        }
+    }
+}
+
+// narrows the selection down to the clicked unit, or with Ctrl to every selected unit of its type
+fn selection_portrait_interaction(
+    mut cmds: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut q_portrait: Query<
+        (&Interaction, &mut BorderColor, &SelectionPortrait),
+        Changed<Interaction>,
+    >,
+    q_selected: Query<(Entity, &UnitType), With<SelectedUnit>>,
+) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+
+    for (interaction, mut border_clr, portrait) in q_portrait.iter_mut() {
+        match interaction {
+            Interaction::None => border_clr.0 = CLR_STRUCTURE_BUILD_ACTIONS,
+            Interaction::Hovered => border_clr.0 = CLR_STRUCTURE_BUILD_ACTIONS_HVR,
+            Interaction::Pressed => {
+                let Ok((_, unit_type)) = q_selected.get(portrait.0) else {
+                    continue;
+                };
+
+                let units = match ctrl {
+                    true => q_selected
+                        .iter()
+                        .filter(|(_, other_type)| *other_type == unit_type)
+                        .map(|(unit_ent, _)| unit_ent)
+                        .collect(),
+                    false => vec![portrait.0],
+                };
+
+                cmds.trigger(DeselectAllUnitsEv);
+                cmds.trigger(SelectUnitsEv(units));
+            }
+        }
+    }
+}
+
+fn toggle_orders_ctr(
+    game_cmds: Res<GameCommands>,
+    mut q_orders_ctr: Query<&mut Node, With<OrdersCtr>>,
+) {
+    let Ok(mut node) = q_orders_ctr.single_mut() else {
+        return;
+    };
+
+    let display = match game_cmds.is_any_unit_selected {
+        true => Display::Flex,
+        false => Display::None,
+    };
+
+    if node.display != display {
+        node.display = display;
+    }
+}
+
 fn build_unit_btn_interaction(
     mut cmds: Commands,
     mut q_btn_unit: Query<(&Interaction, &mut ImageNode, &UnitCtr), With<UnitCtr>>,
@@ -120,9 +242,56 @@ fn build_unit_btn_interaction(
                 info_ctr_data.cost = unit_ctr.0.cost();
                 info_ctr_data.build_time = unit_ctr.0.build_time();
                 info_ctr_data.hp = Some(unit_ctr.0.hp());
-                info_ctr_data.dmg = Some(unit_ctr.0.dmg());
+                info_ctr_data.dmg = unit_ctr.0.dmg();
                 info_ctr_data.speed = Some(unit_ctr.0.speed());
+                info_ctr_data.range = unit_ctr.0.range();
+                info_ctr_data.rate_of_fire = unit_ctr.0.rate_of_fire();
+                img.color = CLR_STRUCTURE_BUILD_ACTIONS_HVR;
+
+                if input.just_pressed(MouseButton::Right) {
+                    cmds.trigger(CancelUnitEv(unit_ctr.0));
+                }
+            }
+        }
+    }
+}
+
+fn research_btn_interaction(
+    mut cmds: Commands,
+    mut q_btn_research: Query<(&Interaction, &mut ImageNode, &ResearchCtr)>,
+    mut info_ctr_data: ResMut<InfoContainerData>,
+    input: Res<ButtonInput<MouseButton>>,
+) {
+    for (interaction, mut img, research_ctr) in q_btn_research.iter_mut() {
+        let research = research_ctr.0;
+
+        match interaction {
+            Interaction::None => {
+                img.color = CLR_STRUCTURE_BUILD_ACTIONS;
+            }
+            Interaction::Pressed => {
+                info_ctr_data.active = true;
+                img.color = CLR_STRUCTURE_BUILD_ACTIONS_HVR;
+
+                if input.just_pressed(MouseButton::Left) {
+                    cmds.trigger(QueueResearchEv(research));
+                }
+            }
+            Interaction::Hovered => {
+                info_ctr_data.active = true;
+                info_ctr_data.name = research.name();
+                info_ctr_data.cost = research.cost();
+                info_ctr_data.build_time = research.duration();
+                info_ctr_data.hp = None;
+                info_ctr_data.dmg = None;
+                info_ctr_data.speed = None;
+                info_ctr_data.range = None;
+                info_ctr_data.rate_of_fire = None;
                 img.color = CLR_STRUCTURE_BUILD_ACTIONS_HVR;
+
+                if input.just_pressed(MouseButton::Right) {
+                    cmds.trigger(CancelResearchEv(research));
+                }
             }
         }
     }
@@ -158,7 +327,7 @@ fn select_structure(
         cmds.entity(placeholder_ent).despawn();
     }
 
-    let placeholder_properties = placeholder.build_placeholder(my_models);
+    let placeholder_properties = placeholder.build_placeholder(&my_models);
     let transform = Transform::from_xyz(100000.0, 0.0, 0.0); // avoid bug flicker
 
     *cursor_state = CursorState::Build;
@@ -181,12 +350,16 @@ fn toggle_info_ctr(
         Query<&mut Text, With<InfoCtrBuildTimeTxt>>,
         Query<&mut Text, With<InfoCtrName>>,
         Query<&mut Text, With<InfoCtrCost>>,
+        Query<&mut Text, With<InfoCtrRangeTxt>>,
+        Query<&mut Text, With<InfoCtrRateOfFireTxt>>,
     )>,
     mut ctr_set: ParamSet<(
         Query<&mut Node, With<InfoCtrDmg>>,
         Query<&mut Node, With<InfoCtrSpeed>>,
         Query<&mut Node, With<InfoCtrHp>>,
         Query<(&mut Node, &mut Visibility), With<InfoCtr>>,
+        Query<&mut Node, With<InfoCtrRange>>,
+        Query<&mut Node, With<InfoCtrRateOfFire>>,
     )>,
 ) {
     let Ok(cmd_interface_node) = q_cmd_interface.single() else {
@@ -255,4 +428,28 @@ fn toggle_info_ctr(
             }
         }
     }
+
+    // Range
+    if let Ok(mut range_txt) = set.p6().single_mut() {
+        if let Ok(mut range_ctr) = ctr_set.p4().single_mut() {
+            if let Some(range) = info_ctr_data.range {
+                range_ctr.display = Display::Flex;
+                range_txt.0 = range.to_string();
+            } else {
+                range_ctr.display = Display::None;
+            }
+        }
+    }
+
+    // Rate of Fire
+    if let Ok(mut rate_of_fire_txt) = set.p7().single_mut() {
+        if let Ok(mut rate_of_fire_ctr) = ctr_set.p5().single_mut() {
+            if let Some(rate_of_fire) = info_ctr_data.rate_of_fire {
+                rate_of_fire_ctr.display = Display::Flex;
+                rate_of_fire_txt.0 = format!("{}s", rate_of_fire);
+            } else {
+                rate_of_fire_ctr.display = Display::None;
+            }
+        }
+    }
 }
@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 
-use crate::units::components::UnitType;
+use crate::research::components::ResearchType;
+use crate::units::components::{UnitOrder, UnitType};
 
 #[derive(Component)]
 pub struct CmdInterfaceCtr;
@@ -8,9 +9,47 @@ pub struct CmdInterfaceCtr;
 #[derive(Component)]
 pub struct UnitCtr(pub UnitType);
 
+#[derive(Component)]
+pub struct ResearchCtr(pub ResearchType);
+
 #[derive(Component)]
 pub struct InfoCtr;
 
+// only shown while units are selected
+#[derive(Component)]
+pub struct OrdersCtr;
+
+#[derive(Component)]
+pub struct OrderBtn(pub UnitOrder);
+
+// cycles through the formations, labelled with the current one
+#[derive(Component)]
+pub struct FormationBtn;
+
+// cycles when health bars are shown, labelled with the current setting
+#[derive(Component)]
+pub struct HealthBarsBtn;
+
+// what is selected, as portraits grouped by unit type or the full stats of a single unit
+#[derive(Component)]
+pub struct SelectionPanel;
+
+#[derive(Component)]
+pub struct SelectionPortrait(pub Entity);
+
+#[derive(Component)]
+pub struct SelectionHealthBar(pub Entity);
+
+#[derive(Component)]
+pub struct SelectionHpTxt(pub Entity);
+
+#[derive(Component)]
+pub struct MiniMapCtr;
+
+// the part of the mini map that shows the world
+#[derive(Component)]
+pub struct MiniMapView;
+
 #[derive(Component)]
 pub struct InfoCtrCost;
 
@@ -41,8 +80,26 @@ pub struct InfoCtrHp;
 #[derive(Component)]
 pub struct InfoCtrHpTxt;
 
+#[derive(Component)]
+pub struct InfoCtrRange;
+
+#[derive(Component)]
+pub struct InfoCtrRangeTxt;
+
+#[derive(Component)]
+pub struct InfoCtrRateOfFire;
+
+#[derive(Component)]
+pub struct InfoCtrRateOfFireTxt;
+
 #[derive(Component)]
 pub struct UnitBuildColumn;
 
+#[derive(Component)]
+pub struct ResearchBuildColumn;
+
 #[derive(Component)]
 pub struct BankTxt;
+
+#[derive(Component)]
+pub struct PowerTxt;
@@ -8,3 +8,11 @@ pub struct BuildStructureSelectEv(pub StructureType);
 
 #[derive(Event)]
 pub struct BuildUnitEv(pub UnitType);
+
+// Event when user cancels the most recently started construction of a structure type
+#[derive(Event)]
+pub struct CancelStructureEv(pub StructureType);
+
+// Event when user cancels the most recently queued unit of a type
+#[derive(Event)]
+pub struct CancelUnitEv(pub UnitType);
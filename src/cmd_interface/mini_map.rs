@@ -0,0 +1,269 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_rts_camera::RtsCamera;
+use bevy_rts_pathfinding::components as pf_comps;
+use std::collections::HashSet;
+
+use super::components::*;
+use crate::harvesting::components::ResourceField;
+use crate::structures::components::Structure;
+use crate::structures::resources::StructuresBuilt;
+use crate::teams::Team;
+use crate::units::components::Unit;
+use crate::utils;
+use crate::{MAP_DEPTH, MAP_WIDTH};
+
+const ICON_SIZE_UNIT: f32 = 4.0;
+const ICON_SIZE_STRUCTURE: f32 = 8.0;
+const ICON_SIZE_RESOURCE: f32 = 10.0;
+const CLR_RESOURCE: Color = Color::srgb(0.95, 0.8, 0.2);
+const CLR_FRUSTUM: Color = Color::WHITE;
+
+pub struct MiniMapPlugin;
+
+impl Plugin for MiniMapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                toggle_mini_map.run_if(resource_changed::<StructuresBuilt>),
+                (
+                    spawn_mini_map_icons,
+                    sync_mini_map_icons,
+                    sync_mini_map_frustum,
+                    move_camera_from_mini_map,
+                )
+                    .run_if(mini_map_active),
+            ),
+        );
+    }
+}
+
+// something in the world that shows up on the mini map
+#[derive(Component)]
+struct MiniMapIcon(Entity);
+
+#[derive(Component)]
+struct MiniMapFrustum;
+
+fn mini_map_active(structures_built: Res<StructuresBuilt>) -> bool {
+    structures_built.satellite_dish > 0
+}
+
+// the mini map only works while a satellite dish is up
+fn toggle_mini_map(
+    structures_built: Res<StructuresBuilt>,
+    mut q_view: Query<&mut Visibility, With<MiniMapView>>,
+) {
+    let Ok(mut vis) = q_view.single_mut() else {
+        return;
+    };
+
+    *vis = if structures_built.satellite_dish > 0 {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+}
+
+// world (x, z) to a position on the mini map, in percent
+fn to_mini_map(pos: Vec3) -> Vec2 {
+    let x = (pos.x + MAP_WIDTH / 2.0) / MAP_WIDTH;
+    let y = (pos.z + MAP_DEPTH / 2.0) / MAP_DEPTH;
+    Vec2::new(x, y).clamp(Vec2::ZERO, Vec2::ONE) * 100.0
+}
+
+fn spawn_mini_map_icons(
+    mut cmds: Commands,
+    q_view: Query<Entity, With<MiniMapView>>,
+    q_icon: Query<&MiniMapIcon>,
+    q_unit: Query<(Entity, &Team), With<Unit>>,
+    q_structure: Query<(Entity, &Team), With<Structure>>,
+    q_resource: Query<Entity, With<ResourceField>>,
+) {
+    let Ok(view_ent) = q_view.single() else {
+        return;
+    };
+
+    let tracked: HashSet<Entity> = q_icon.iter().map(|icon| icon.0).collect();
+
+    let units = q_unit
+        .iter()
+        .map(|(ent, team)| (ent, team.color(), ICON_SIZE_UNIT));
+    let structures = q_structure
+        .iter()
+        .map(|(ent, team)| (ent, team.color(), ICON_SIZE_STRUCTURE));
+    let resources = q_resource
+        .iter()
+        .map(|ent| (ent, CLR_RESOURCE, ICON_SIZE_RESOURCE));
+
+    for (ent, color, size) in units.chain(structures).chain(resources) {
+        if tracked.contains(&ent) {
+            continue;
+        }
+
+        let icon = (
+            MiniMapIcon(ent),
+            BackgroundColor(color),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Px(size),
+                height: Val::Px(size),
+                margin: UiRect::new(
+                    Val::Px(-size / 2.0),
+                    Val::ZERO,
+                    Val::Px(-size / 2.0),
+                    Val::ZERO,
+                ),
+                ..default()
+            },
+            Pickable::IGNORE,
+            Name::new("Mini Map Icon"),
+        );
+
+        cmds.entity(view_ent).with_child(icon);
+    }
+}
+
+fn sync_mini_map_icons(
+    mut cmds: Commands,
+    mut q_icon: Query<(Entity, &MiniMapIcon, &mut Node, &mut Visibility)>,
+    q_trans: Query<(&Transform, &Visibility), Without<MiniMapIcon>>,
+) {
+    for (icon_ent, icon, mut node, mut icon_vis) in q_icon.iter_mut() {
+        let Ok((trans, vis)) = q_trans.get(icon.0) else {
+            cmds.entity(icon_ent).despawn();
+            continue;
+        };
+
+        // whatever is hidden in the world (enemies in the fog) is hidden here too
+        let new_vis = if *vis == Visibility::Hidden {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+        icon_vis.set_if_neq(new_vis);
+
+        let pos = to_mini_map(trans.translation);
+        node.left = Val::Percent(pos.x);
+        node.top = Val::Percent(pos.y);
+    }
+}
+
+// outlines the part of the ground the camera can currently see
+fn sync_mini_map_frustum(
+    mut cmds: Commands,
+    q_view: Query<Entity, With<MiniMapView>>,
+    mut q_frustum: Query<&mut Node, With<MiniMapFrustum>>,
+    q_cam: Query<(&Camera, &GlobalTransform), With<RtsCamera>>,
+    q_map_base: Query<&GlobalTransform, With<pf_comps::MapBase>>,
+) {
+    let Ok((cam, cam_trans)) = q_cam.single() else {
+        return;
+    };
+
+    let Ok(map_base) = q_map_base.single() else {
+        return;
+    };
+
+    let Some(viewport) = cam.logical_viewport_size() else {
+        return;
+    };
+
+    let corners = [
+        Vec2::ZERO,
+        Vec2::new(viewport.x, 0.0),
+        Vec2::new(0.0, viewport.y),
+        viewport,
+    ];
+
+    let ground: Vec<Vec2> = corners
+        .iter()
+        .filter_map(|corner| utils::get_world_coords(map_base, cam_trans, cam, *corner))
+        .map(to_mini_map)
+        .collect();
+
+    if ground.len() < corners.len() {
+        return;
+    }
+
+    let min = ground.iter().fold(Vec2::splat(100.0), |acc, p| acc.min(*p));
+    let max = ground.iter().fold(Vec2::ZERO, |acc, p| acc.max(*p));
+
+    let Ok(mut node) = q_frustum.single_mut() else {
+        let Ok(view_ent) = q_view.single() else {
+            return;
+        };
+
+        let frustum = (
+            MiniMapFrustum,
+            BorderColor(CLR_FRUSTUM),
+            Node {
+                position_type: PositionType::Absolute,
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            ZIndex(1),
+            Pickable::IGNORE,
+            Name::new("Mini Map Frustum"),
+        );
+
+        cmds.entity(view_ent).with_child(frustum);
+        return;
+    };
+
+    node.left = Val::Percent(min.x);
+    node.top = Val::Percent(min.y);
+    node.width = Val::Percent(max.x - min.x);
+    node.height = Val::Percent(max.y - min.y);
+}
+
+// clicking (or dragging across) the mini map moves the camera there. only a press that starts
+// on the mini map counts, so a drag select passing over it leaves the camera alone
+fn move_camera_from_mini_map(
+    input: Res<ButtonInput<MouseButton>>,
+    q_view: Query<(&ComputedNode, &GlobalTransform), With<MiniMapView>>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_cam: Query<&mut RtsCamera>,
+    mut is_dragging: Local<bool>,
+) {
+    if !input.pressed(MouseButton::Left) {
+        *is_dragging = false;
+        return;
+    }
+
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+
+    let Some(cursor) = window.physical_cursor_position() else {
+        return;
+    };
+
+    let Ok((node, node_trans)) = q_view.single() else {
+        return;
+    };
+
+    // ui transforms sit at the center of the node, in physical pixels
+    let size = node.size();
+    let top_left = node_trans.translation().truncate() - size / 2.0;
+    let normalized = (cursor - top_left) / size;
+
+    if input.just_pressed(MouseButton::Left) {
+        *is_dragging = normalized.cmpge(Vec2::ZERO).all() && normalized.cmple(Vec2::ONE).all();
+    }
+
+    if !*is_dragging {
+        return;
+    }
+
+    // dragging off the edge keeps the camera at the edge of the map
+    let normalized = normalized.clamp(Vec2::ZERO, Vec2::ONE);
+
+    let Ok(mut rts_cam) = q_cam.single_mut() else {
+        return;
+    };
+
+    rts_cam.target_focus.translation.x = (normalized.x - 0.5) * MAP_WIDTH;
+    rts_cam.target_focus.translation.z = (normalized.y - 0.5) * MAP_DEPTH;
+}
@@ -3,10 +3,12 @@ use bevy::prelude::*;
 mod build_actions;
 pub mod components;
 pub mod events;
+mod mini_map;
 pub mod resources;
 pub mod ui;
 
 use build_actions::BuildActionsPlugin;
+use mini_map::MiniMapPlugin;
 use resources::ResourcesPlugin;
 use ui::UiPlugin;
 
@@ -14,6 +16,6 @@ pub struct CmdInterfacePlugin;
 
 impl Plugin for CmdInterfacePlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((UiPlugin, BuildActionsPlugin, ResourcesPlugin));
+        app.add_plugins((UiPlugin, BuildActionsPlugin, MiniMapPlugin, ResourcesPlugin));
     }
 }
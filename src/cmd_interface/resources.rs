@@ -21,6 +21,8 @@ pub struct InfoContainerData {
     pub hp: Option<i32>,
     pub dmg: Option<i32>,
     pub speed: Option<f32>,
+    pub range: Option<f32>,
+    pub rate_of_fire: Option<f32>,
 }
 
 #[derive(Resource, Default)]
@@ -32,13 +34,9 @@ impl BuildQueueCount {
     }
 
     pub fn remove(&mut self, unit: &UnitType) {
-        *self.0.entry(*unit).or_insert(0) -= 1;
-
-        // if let Some(count) = self.0.get_mut(unit) {
-        //     if *count > 0 {
-        //         *count -= 1;
-        //     }
-        // }
+        if let Some(count) = self.0.get_mut(unit) {
+            *count = count.saturating_sub(1);
+        }
     }
 
     pub fn get(&self, unit: &UnitType) -> usize {
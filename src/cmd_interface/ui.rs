@@ -9,13 +9,25 @@ use super::resources::BuildQueueCount;
 use super::{build_actions::CLR_STRUCTURE_BUILD_ACTIONS, components::*};
 use crate::asset_manager::imgs::MyImgs;
 use crate::bank::Bank;
+use crate::combat::components::{Health, Weapon};
+use crate::health_bars::HealthBars;
+use crate::research::components::{ResearchQueue, ResearchType};
+use crate::research::resources::CompletedResearch;
 use crate::resources::DbgOptions;
+use crate::structures::components::BuildQueue;
 use crate::structures::components::StructureType;
-use crate::structures::resources::VehicleBuildQueue;
-use crate::units::components::UnitType;
-use crate::units::resources::UnlockedUnits;
+use crate::structures::resources::StructuresBuilt;
+use crate::teams::Team;
+use crate::units::components::{SelectedUnit, Speed, UnitOrder, UnitType};
+use crate::units::resources::{Formation, UnlockedUnits};
+use crate::COLOR_GROUND;
 
 const CLR_BUILD_PROGRESS_BAR: Color = Color::srgba(1.0, 1.0, 1.0, 0.075);
+const CLR_HEALTH_BAR_BG: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+const CLR_HEALTH_HIGH: Color = Color::srgb(0.2, 0.8, 0.2);
+const CLR_HEALTH_MID: Color = Color::srgb(0.9, 0.75, 0.1);
+const CLR_HEALTH_LOW: Color = Color::srgb(0.9, 0.2, 0.2);
+const PORTRAIT_SIZE: f32 = 40.0;
 
 pub struct UiPlugin;
 
@@ -27,8 +39,16 @@ impl Plugin for UiPlugin {
                 update_build_queue_count.run_if(resource_changed::<BuildQueueCount>),
                 update_minimap_aspect,
                 update_build_progress_bar,
+                update_research_progress,
                 update_scroll_position,
                 spawn_unit_ctrs.run_if(resource_changed::<UnlockedUnits>),
+                update_formation_btn.run_if(resource_changed::<Formation>),
+                update_health_bars_btn.run_if(resource_changed::<HealthBars>),
+                rebuild_selection_panel,
+                update_selection_health.after(rebuild_selection_panel),
+                spawn_research_ctrs.run_if(
+                    resource_changed::<CompletedResearch>.or(resource_changed::<StructuresBuilt>),
+                ),
             ),
         );
     }
@@ -41,10 +61,13 @@ struct BuildQueueCountCtr(UnitType);
 struct BuildUnitProgressBar(UnitType);
 
 #[derive(Component)]
-struct OptCtr;
+struct ResearchProgressBar(ResearchType);
 
 #[derive(Component)]
-struct MiniMapCtr;
+struct ResearchQueuedTxt(ResearchType);
+
+#[derive(Component)]
+struct OptCtr;
 
 #[derive(Component)]
 struct BuildColumnsCtr;
@@ -64,17 +87,59 @@ struct Artillery;
 #[derive(Component)]
 struct RiflemanCtr;
 
+#[derive(Component)]
+struct HarvesterCtr;
+
+// the map is square, so the mini map is kept as tall as it is wide
 fn update_minimap_aspect(mut q_mini_map: Query<(&mut Node, &ComputedNode), With<MiniMapCtr>>) {
-    // if let Ok((mut mini_map, computed_node)) = q_mini_map.single_mut() {
-    //     let width = computed_node.size().x;
+    if let Ok((mut mini_map, computed_node)) = q_mini_map.single_mut() {
+        let width = computed_node.size().x * computed_node.inverse_scale_factor();
+
+        // first frame is 0.0 for some reason
+        if width == 0.0 {
+            return;
+        }
+
+        if mini_map.height != Val::Px(width) {
+            mini_map.height = Val::Px(width);
+        }
+    }
+}
+
+// rows of the info ctr, also used for the stats of a single selected unit
+fn create_ctr<T>(ctr: T, name: &str) -> (T, Node, Name) {
+    (
+        ctr,
+        Node {
+            padding: UiRect::new(Val::Px(5.0), Val::ZERO, Val::Px(5.0), Val::Px(5.0)),
+            ..default()
+        },
+        Name::new(name.to_string()),
+    )
+}
 
-    //     // first frame is 0.0 for some reason
-    //     if width == 0.0 {
-    //         return;
-    //     }
+fn info_ctr_icon(img: Handle<Image>, name: String) -> (ImageNode, Node, Name) {
+    (
+        ImageNode::new(img),
+        Node {
+            margin: UiRect::right(Val::Px(7.5)),
+            ..default()
+        },
+        Name::new(name),
+    )
+}
 
-    //     mini_map.height = Val::Px(width);
-    // }
+// no icons exist for these stats yet, so they get a short text label instead
+fn info_ctr_label(txt: &str) -> (Text, TextFont, Node, Name) {
+    (
+        Text::new(txt),
+        TextFont::from_font_size(12.0),
+        Node {
+            margin: UiRect::right(Val::Px(7.5)),
+            ..default()
+        },
+        Name::new(format!("{} Label", txt)),
+    )
 }
 
 fn command_center_ui(
@@ -92,7 +157,7 @@ fn command_center_ui(
             padding: UiRect::all(Val::Px(10.0)),
             align_self: AlignSelf::FlexStart,
             width: Val::Px(200.0),
-            height: Val::Px(200.0),
+            min_height: Val::Px(200.0),
             top: Val::Percent(50.0),
             ..default()
         },
@@ -100,28 +165,6 @@ fn command_center_ui(
         Name::new("Info Ctr"),
     );
 
-    fn create_ctr<T>(ctr: T, name: &str) -> (T, Node, Name) {
-        (
-            ctr,
-            Node {
-                padding: UiRect::new(Val::Px(5.0), Val::ZERO, Val::Px(5.0), Val::Px(5.0)),
-                ..default()
-            },
-            Name::new(name.to_string()),
-        )
-    }
-
-    let info_ctr_icon = |img: Handle<Image>, name: String| -> (ImageNode, Node, Name) {
-        (
-            ImageNode::new(img),
-            Node {
-                margin: UiRect::right(Val::Px(7.5)),
-                ..default()
-            },
-            Name::new(name),
-        )
-    };
-
     // Info Ctr Data
     let name = (InfoCtrName, Text::new("Building Name"), Name::new("Name"));
     let cost = (InfoCtrCost, Text::new("$1000"), Name::new("Cost"));
@@ -133,6 +176,12 @@ fn command_center_ui(
         Text::new(""),
         Name::new("Build Time Txt"),
     );
+    let range_txt = (InfoCtrRangeTxt, Text::new(""), Name::new("Range Txt"));
+    let rate_of_fire_txt = (
+        InfoCtrRateOfFireTxt,
+        Text::new(""),
+        Name::new("Rate of Fire Txt"),
+    );
 
     let cmd_interface_ctr = (
         BackgroundColor(Color::BLACK),
@@ -151,6 +200,55 @@ fn command_center_ui(
         Name::new("Command Interface Ctr"),
     );
 
+    // sits along the left edge of the command interface
+    let orders_ctr = (
+        OrdersCtr,
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Percent(100.0),
+            bottom: Val::Px(10.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(5.0),
+            margin: UiRect::right(Val::Px(10.0)),
+            display: Display::None,
+            ..default()
+        },
+        Name::new("Orders Ctr"),
+    );
+
+    // always shown, unlike the orders
+    let health_bars_ctr = (
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Percent(100.0),
+            top: Val::Px(10.0),
+            margin: UiRect::right(Val::Px(10.0)),
+            ..default()
+        },
+        Name::new("Health Bars Ctr"),
+    );
+
+    let order_btn = |txt: &str| {
+        (
+            Button,
+            BackgroundColor(Color::BLACK),
+            BorderColor(CLR_STRUCTURE_BUILD_ACTIONS),
+            BorderRadius::all(Val::Px(6.5)),
+            Node {
+                width: Val::Px(110.0),
+                padding: UiRect::all(Val::Px(5.0)),
+                border: UiRect::all(Val::Px(2.5)),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            children![(
+                Text::new(txt),
+                TextFont::from_font_size(16.0),
+                Pickable::IGNORE,
+            )],
+        )
+    };
+
     let mini_map_ctr = (
         MiniMapCtr,
         ImageNode::new(my_imgs.cmd_intrfce_mini_map.clone()),
@@ -162,6 +260,23 @@ fn command_center_ui(
         Name::new("Mini Map Ctr"),
     );
 
+    // stays hidden until a satellite dish is built
+    let mini_map_view = (
+        MiniMapView,
+        BackgroundColor(COLOR_GROUND),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.0),
+            right: Val::Px(10.0),
+            top: Val::Px(10.0),
+            bottom: Val::Px(10.0),
+            overflow: Overflow::clip(),
+            ..default()
+        },
+        Visibility::Hidden,
+        Name::new("Mini Map View"),
+    );
+
     let bank_ctr = (
         ImageNode::new(my_imgs.cmd_intrfce_funds.clone()),
         Node {
@@ -187,6 +302,21 @@ fn command_center_ui(
         Name::new("Bank Txt"),
     );
 
+    let power_txt = (
+        PowerTxt,
+        Node {
+            margin: UiRect::all(Val::Auto),
+            ..default()
+        },
+        Text::new("PWR 0/0"),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+        TextLayout::new_with_justify(JustifyText::Center),
+        Name::new("Power Txt"),
+    );
+
     let icons_ctr = (
         IconsCtr,
         Node {
@@ -224,6 +354,17 @@ fn command_center_ui(
         Name::new("Build Columns Ctr"),
     );
 
+    let opts_ctr = |name: &str| -> (Node, Name) {
+        (
+            Node {
+                flex_direction: FlexDirection::Column,
+                width: Val::Percent(100.0),
+                ..default()
+            },
+            Name::new(name.to_string()),
+        )
+    };
+
     let build_column = |margin_l: f32, margin_r: f32| -> (Node, Name) {
         (
             Node {
@@ -325,15 +466,72 @@ fn command_center_ui(
                 ));
                 p.spawn(speed_txt);
             });
+        p.spawn(create_ctr(InfoCtrRange, "Range Ctr"))
+            .with_children(|p| {
+                p.spawn(info_ctr_label("RNG"));
+                p.spawn(range_txt);
+            });
+        p.spawn(create_ctr(InfoCtrRateOfFire, "Rate of Fire Ctr"))
+            .with_children(|p| {
+                p.spawn(info_ctr_label("ROF"));
+                p.spawn(rate_of_fire_txt);
+            });
     });
 
+    // Selection Panel, filled in by rebuild_selection_panel
+    cmds.spawn((
+        SelectionPanel,
+        Button,
+        ImageNode::new(my_imgs.info_ctr.clone()),
+        Node {
+            flex_direction: FlexDirection::Column,
+            position_type: PositionType::Absolute,
+            padding: UiRect::all(Val::Px(10.0)),
+            row_gap: Val::Px(5.0),
+            left: Val::Px(10.0),
+            bottom: Val::Px(10.0),
+            min_width: Val::Px(200.0),
+            max_width: Val::Px(360.0),
+            display: Display::None,
+            ..default()
+        },
+        ZIndex(105),
+        Name::new("Selection Panel"),
+    ));
+
     // Command Interface Ctr
     cmds.spawn(cmd_interface_ctr).with_children(|p| {
+        // unit orders
+        p.spawn(orders_ctr).with_children(|p| {
+            for order in UnitOrder::iter() {
+                p.spawn((
+                    order_btn(order.label()),
+                    OrderBtn(order),
+                    Name::new(format!("{:?} Order Btn", order)),
+                ));
+            }
+
+            p.spawn((
+                order_btn(Formation::default().label()),
+                FormationBtn,
+                Name::new("Formation Btn"),
+            ));
+        });
+
+        p.spawn(health_bars_ctr).with_child((
+            order_btn(HealthBars::default().label()),
+            HealthBarsBtn,
+            Name::new("Health Bars Btn"),
+        ));
+
         // mini map
-        p.spawn(mini_map_ctr);
+        p.spawn(mini_map_ctr).with_child(mini_map_view);
 
         // bank
-        p.spawn(bank_ctr).with_child(bank_txt);
+        p.spawn(bank_ctr).with_children(|p| {
+            p.spawn(bank_txt);
+            p.spawn(power_txt);
+        });
 
         // structure/units
         p.spawn(build_columns_ctr).with_children(|p| {
@@ -347,8 +545,11 @@ fn command_center_ui(
                 }
             });
 
-            // Units Column
-            p.spawn((build_column(5.0, 2.5), UnitBuildColumn));
+            // Units Column, research options are listed below the units
+            p.spawn(build_column(5.0, 2.5)).with_children(|p| {
+                p.spawn((opts_ctr("Unit Options"), UnitBuildColumn));
+                p.spawn((opts_ctr("Research Options"), ResearchBuildColumn));
+            });
         });
     });
 
@@ -359,6 +560,34 @@ fn command_center_ui(
     //     });
 }
 
+fn update_formation_btn(
+    formation: Res<Formation>,
+    q_formation_btn: Query<&Children, With<FormationBtn>>,
+    mut q_txt: Query<&mut Text>,
+) {
+    for children in q_formation_btn.iter() {
+        for child in children.iter() {
+            if let Ok(mut txt) = q_txt.get_mut(child) {
+                txt.0 = formation.label().to_string();
+            }
+        }
+    }
+}
+
+fn update_health_bars_btn(
+    health_bars: Res<HealthBars>,
+    q_health_bars_btn: Query<&Children, With<HealthBarsBtn>>,
+    mut q_txt: Query<&mut Text>,
+) {
+    for children in q_health_bars_btn.iter() {
+        for child in children.iter() {
+            if let Ok(mut txt) = q_txt.get_mut(child) {
+                txt.0 = health_bars.label().to_string();
+            }
+        }
+    }
+}
+
 fn update_build_queue_count(
     mut q_build_queue_ctr: Query<(&mut Text, &mut Visibility, &BuildQueueCountCtr)>,
     build_queue_count: Res<BuildQueueCount>,
@@ -404,6 +633,37 @@ fn spawn_unit_ctrs(
         if unlocked_units.artillery {
             spawn_unit_btn(parent, UnitType::Artillery, &my_assets, Artillery);
         }
+        if unlocked_units.harvester {
+            spawn_unit_btn(parent, UnitType::Harvester, &my_assets, HarvesterCtr);
+        }
+    });
+}
+
+fn spawn_research_ctrs(
+    mut cmds: Commands,
+    q_research_build_column: Query<Entity, With<ResearchBuildColumn>>,
+    completed_research: Res<CompletedResearch>,
+    structures_built: Res<StructuresBuilt>,
+    my_assets: Res<MyImgs>,
+) {
+    let Ok(research_build_column) = q_research_build_column.single() else {
+        return;
+    };
+
+    cmds.entity(research_build_column)
+        .despawn_related::<Children>();
+
+    if structures_built.research_center == 0 {
+        return;
+    }
+
+    // only research that can be started right now is listed
+    cmds.entity(research_build_column).with_children(|parent| {
+        for research in ResearchType::iter() {
+            if completed_research.is_available(research) {
+                spawn_research_btn(parent, research, &my_assets);
+            }
+        }
     });
 }
 
@@ -527,6 +787,79 @@ fn spawn_unit_btn<T: Component>(
         });
 }
 
+fn spawn_research_btn(
+    parent: &mut RelatedSpawnerCommands<'_, ChildOf>,
+    research: ResearchType,
+    assets: &Res<MyImgs>,
+) {
+    let research_opt_ctr = (
+        OptCtr,
+        Button,
+        BorderColor(Color::srgb(0.8, 0.8, 0.8)),
+        ImageNode::from(assets.structure_research_center.clone()),
+        Node {
+            width: Val::Percent(100.0),
+            min_width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            margin: UiRect::bottom(Val::Px(5.0)),
+            border: UiRect::all(Val::Px(2.5)),
+            aspect_ratio: Some(1.0),
+            ..default()
+        },
+        ResearchCtr(research),
+        Name::new("Research Option"),
+    );
+
+    let queued_txt = (
+        ResearchQueuedTxt(research),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(5.0),
+            top: Val::Px(5.0),
+            ..default()
+        },
+        Visibility::Hidden,
+        TextFont::from_font_size(15.0),
+        Text::new("Queued"),
+        Name::new("Research Queued Txt"),
+    );
+
+    let progress_bar = (
+        ResearchProgressBar(research),
+        BackgroundColor(CLR_BUILD_PROGRESS_BAR),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            bottom: Val::Px(0.0),
+            ..default()
+        },
+        Visibility::Hidden,
+        Name::new("Research Progress Bar"),
+    );
+
+    parent
+        .spawn(research_opt_ctr)
+        .insert(Pickable {
+            should_block_lower: false,
+            ..default()
+        })
+        .with_children(|p| {
+            p.spawn(queued_txt).insert(Pickable {
+                should_block_lower: false,
+                ..default()
+            });
+            p.spawn(progress_bar).insert(Pickable {
+                should_block_lower: false,
+                ..default()
+            });
+            p.spawn(build_opt_txt(research.name())).insert(Pickable {
+                should_block_lower: false,
+                ..default()
+            });
+        });
+}
+
 fn build_opt_txt(
     txt: String,
 ) -> (
@@ -556,26 +889,34 @@ fn build_opt_txt(
 }
 
 fn update_build_progress_bar(
-    res: Res<VehicleBuildQueue>,
+    q_build_queue: Query<(&BuildQueue, &Team)>,
     mut unit_ctr: Query<(&mut Visibility, &mut Node, &BuildUnitProgressBar)>,
 ) {
-    // Get the first item in the build queue.
-    let Some((unit_type, timer)) = res.0.first() else {
-        return;
-    };
-
-    // Compute progress percent
-    let elapsed_secs = timer.elapsed().as_secs_f32();
-    let total_secs = timer.duration().as_secs_f32();
-    let progress_percent = if total_secs > 0.0 {
-        (elapsed_secs / total_secs) * 100.0
-    } else {
-        0.0
-    };
+    // Only the first item in each of our structures' build queues is in production.
+    let in_production: Vec<&(UnitType, Timer)> = q_build_queue
+        .iter()
+        .filter(|(_, team)| team.is_local())
+        .filter_map(|(build_queue, _)| build_queue.0.first())
+        .collect();
 
     for (mut visibility, mut node, progress_bar) in unit_ctr.iter_mut() {
         // Only update the progress bar for the matching unit type.
-        if progress_bar.0 != *unit_type || progress_percent > 99.5 {
+        // With several structures building the same type, show the one furthest along.
+        let progress = in_production
+            .iter()
+            .filter(|(unit_type, _)| *unit_type == progress_bar.0)
+            .map(|(_, timer)| timer.fraction())
+            .reduce(f32::max);
+
+        let Some(progress) = progress else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        // Compute progress percent
+        let progress_percent = progress * 100.0;
+
+        if progress_percent > 99.5 {
             *visibility = Visibility::Hidden;
             continue;
         }
@@ -585,3 +926,238 @@ fn update_build_progress_bar(
         node.height = Val::Percent(progress_percent);
     }
 }
+
+fn update_research_progress(
+    q_research_queue: Query<(&ResearchQueue, &Team)>,
+    mut q_progress_bar: Query<(&mut Visibility, &mut Node, &ResearchProgressBar)>,
+    mut q_queued_txt: Query<(&mut Visibility, &ResearchQueuedTxt), Without<ResearchProgressBar>>,
+) {
+    let research_queues: Vec<&ResearchQueue> = q_research_queue
+        .iter()
+        .filter(|(_, team)| team.is_local())
+        .map(|(research_queue, _)| research_queue)
+        .collect();
+
+    // only the first research in a queue is in progress
+    let in_progress = |research: ResearchType| -> Option<f32> {
+        research_queues
+            .iter()
+            .filter_map(|research_queue| research_queue.0.first())
+            .find(|(queued, _)| *queued == research)
+            .map(|(_, timer)| timer.fraction())
+    };
+
+    for (mut visibility, mut node, progress_bar) in q_progress_bar.iter_mut() {
+        let Some(progress) = in_progress(progress_bar.0) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        *visibility = Visibility::Visible;
+        node.height = Val::Percent(progress * 100.0);
+    }
+
+    for (mut visibility, queued_txt) in q_queued_txt.iter_mut() {
+        let is_waiting = in_progress(queued_txt.0).is_none()
+            && research_queues
+                .iter()
+                .any(|research_queue| research_queue.contains(queued_txt.0));
+
+        *visibility = if is_waiting {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn health_clr(fraction: f32) -> Color {
+    if fraction > 0.5 {
+        CLR_HEALTH_HIGH
+    } else if fraction > 0.25 {
+        CLR_HEALTH_MID
+    } else {
+        CLR_HEALTH_LOW
+    }
+}
+
+fn health_bar(unit_ent: Entity, height: f32) -> impl Bundle {
+    (
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Px(height),
+            ..default()
+        },
+        BackgroundColor(CLR_HEALTH_BAR_BG),
+        Pickable::IGNORE,
+        Name::new("Health Bar"),
+        children![(
+            SelectionHealthBar(unit_ent),
+            Node {
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(CLR_HEALTH_HIGH),
+            Pickable::IGNORE,
+            Name::new("Health Bar Fill"),
+        )],
+    )
+}
+
+// rebuilt whenever the selection changes. a single unit gets its full stats, a group gets a
+// portrait per unit, grouped by type
+fn rebuild_selection_panel(
+    mut cmds: Commands,
+    my_imgs: Res<MyImgs>,
+    mut q_panel: Query<(Entity, &mut Node), With<SelectionPanel>>,
+    q_added: Query<(), Added<SelectedUnit>>,
+    mut removed: RemovedComponents<SelectedUnit>,
+    q_selected: Query<(Entity, &UnitType, &Health, Option<&Weapon>, &Speed), With<SelectedUnit>>,
+) {
+    let removed_any = removed.read().count() > 0;
+    if q_added.is_empty() && !removed_any {
+        return;
+    }
+
+    let Ok((panel_ent, mut panel_node)) = q_panel.single_mut() else {
+        return;
+    };
+
+    cmds.entity(panel_ent).despawn_related::<Children>();
+
+    let selected: Vec<_> = q_selected.iter().collect();
+    panel_node.display = match selected.is_empty() {
+        true => Display::None,
+        false => Display::Flex,
+    };
+
+    if let [(unit_ent, unit_type, health, weapon, speed)] = selected[..] {
+        cmds.entity(panel_ent).with_children(|p| {
+            p.spawn((Text::new(unit_type.name()), Name::new("Name")));
+            p.spawn(create_ctr((), "HP Ctr")).with_children(|p| {
+                p.spawn(info_ctr_icon(
+                    my_imgs.info_ctr_hp.clone(),
+                    "HP Icon".to_string(),
+                ));
+                p.spawn((
+                    SelectionHpTxt(unit_ent),
+                    Text::new(format!("{}/{}", health.current, health.max)),
+                    Name::new("HP Txt"),
+                ));
+            });
+
+            // harvesters don't fight
+            if let Some(weapon) = weapon {
+                p.spawn(create_ctr((), "Dmg Ctr")).with_children(|p| {
+                    p.spawn(info_ctr_icon(
+                        my_imgs.info_ctr_dmg.clone(),
+                        "Dmg Icon".to_string(),
+                    ));
+                    p.spawn((Text::new(weapon.dmg.to_string()), Name::new("Dmg Txt")));
+                });
+            }
+
+            p.spawn(create_ctr((), "Speed Ctr")).with_children(|p| {
+                p.spawn(info_ctr_icon(
+                    my_imgs.info_ctr_speed.clone(),
+                    "Speed Icon".to_string(),
+                ));
+                p.spawn((Text::new(speed.0.to_string()), Name::new("Speed Txt")));
+            });
+
+            if let Some(weapon) = weapon {
+                let rate_of_fire = weapon.cooldown.duration().as_secs_f32();
+
+                p.spawn(create_ctr((), "Range Ctr")).with_children(|p| {
+                    p.spawn(info_ctr_label("RNG"));
+                    p.spawn((Text::new(weapon.range.to_string()), Name::new("Range Txt")));
+                });
+                p.spawn(create_ctr((), "Rate of Fire Ctr"))
+                    .with_children(|p| {
+                        p.spawn(info_ctr_label("ROF"));
+                        p.spawn((
+                            Text::new(format!("{}s", rate_of_fire)),
+                            Name::new("Rate of Fire Txt"),
+                        ));
+                    });
+            }
+
+            p.spawn(health_bar(unit_ent, 6.0));
+        });
+
+        return;
+    }
+
+    cmds.entity(panel_ent).with_children(|p| {
+        for unit_type in UnitType::iter() {
+            let units: Vec<Entity> = selected
+                .iter()
+                .filter(|(_, t, _, _, _)| **t == unit_type)
+                .map(|(unit_ent, _, _, _, _)| *unit_ent)
+                .collect();
+
+            if units.is_empty() {
+                continue;
+            }
+
+            p.spawn((
+                Text::new(format!("{} x{}", unit_type.name(), units.len())),
+                TextFont::from_font_size(12.0),
+                Name::new(format!("{} Count", unit_type.name())),
+            ));
+
+            let grid = (
+                Node {
+                    flex_wrap: FlexWrap::Wrap,
+                    column_gap: Val::Px(4.0),
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                Name::new(format!("{} Portraits", unit_type.name())),
+            );
+
+            p.spawn(grid).with_children(|p| {
+                for unit_ent in units {
+                    p.spawn((
+                        SelectionPortrait(unit_ent),
+                        Button,
+                        ImageNode::new(unit_type.img(&my_imgs)),
+                        BorderColor(CLR_STRUCTURE_BUILD_ACTIONS),
+                        Node {
+                            width: Val::Px(PORTRAIT_SIZE),
+                            height: Val::Px(PORTRAIT_SIZE),
+                            border: UiRect::all(Val::Px(1.0)),
+                            flex_direction: FlexDirection::Column,
+                            justify_content: JustifyContent::FlexEnd,
+                            ..default()
+                        },
+                        Name::new("Selection Portrait"),
+                    ))
+                    .with_child(health_bar(unit_ent, 4.0));
+                }
+            });
+        }
+    });
+}
+
+fn update_selection_health(
+    mut q_bar: Query<(&mut Node, &mut BackgroundColor, &SelectionHealthBar)>,
+    mut q_hp_txt: Query<(&mut Text, &SelectionHpTxt)>,
+    q_health: Query<&Health>,
+) {
+    for (mut node, mut bg_clr, bar) in q_bar.iter_mut() {
+        let Ok(health) = q_health.get(bar.0) else {
+            continue;
+        };
+
+        let fraction = health.fraction();
+        node.width = Val::Percent(fraction * 100.0);
+        bg_clr.0 = health_clr(fraction);
+    }
+
+    for (mut txt, hp_txt) in q_hp_txt.iter_mut() {
+        if let Ok(health) = q_health.get(hp_txt.0) {
+            txt.0 = format!("{}/{}", health.current, health.max);
+        }
+    }
+}
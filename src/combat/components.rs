@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Health {
+    pub current: i32,
+    pub max: i32,
+}
+
+impl Health {
+    pub fn new(max: i32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0
+    }
+
+    pub fn is_damaged(&self) -> bool {
+        self.current < self.max
+    }
+
+    // remaining health as a value between 0.0 and 1.0
+    pub fn fraction(&self) -> f32 {
+        if self.max <= 0 {
+            return 0.0;
+        }
+
+        (self.current as f32 / self.max as f32).clamp(0.0, 1.0)
+    }
+}
+
+// armed structures aim with this, so firing doesn't turn the whole structure (collider and
+// pathfinding footprint included). a world rotation, none until the turret first takes aim
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct Turret {
+    pub aim: Option<Quat>,
+}
+
+// the node of the structure's model that shows the turret's aim
+#[derive(Component, Debug, Clone, Copy)]
+pub struct TurretNode(pub Entity);
+
+// entity a weapon is currently firing at
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Target(pub Entity);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trajectory {
+    Direct,
+    Ballistic,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct Weapon {
+    pub dmg: i32,
+    pub range: f32,
+    pub cooldown: Timer,
+    pub projectile_speed: f32,
+    pub muzzle_offset: Vec3,
+    pub trajectory: Trajectory,
+}
+
+impl Weapon {
+    pub fn new(
+        dmg: i32,
+        range: f32,
+        rate_of_fire: f32,
+        projectile_speed: f32,
+        muzzle_offset: Vec3,
+        trajectory: Trajectory,
+    ) -> Self {
+        // start loaded, so the first shot goes out as soon as a target is in range
+        let mut cooldown = Timer::from_seconds(rate_of_fire, TimerMode::Once);
+        cooldown.tick(cooldown.duration());
+
+        Self {
+            dmg,
+            range,
+            cooldown,
+            projectile_speed,
+            muzzle_offset,
+            trajectory,
+        }
+    }
+
+    pub fn in_range(&self, from: Vec3, to: Vec3) -> bool {
+        from.xz().distance_squared(to.xz()) <= self.range * self.range
+    }
+}
+
+// a target given by the player, the unit will chase it instead of dropping it when it leaves range
+#[derive(Component, Debug, Clone, Copy)]
+pub struct AttackOrder(pub Entity);
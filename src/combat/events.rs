@@ -0,0 +1,16 @@
+use bevy::prelude::*;
+
+#[derive(Event, Clone, Copy)]
+pub struct DamageEv {
+    pub target: Entity,
+    pub dmg: i32,
+}
+
+impl DamageEv {
+    pub fn new(target: Entity, dmg: i32) -> Self {
+        Self { target, dmg }
+    }
+}
+
+#[derive(Event)]
+pub struct DeathEv(pub Entity);
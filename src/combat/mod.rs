@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+
+pub mod components;
+pub mod events;
+mod targeting;
+mod weapons;
+
+use components::Health;
+use events::*;
+
+use crate::resources::DbgOptions;
+use crate::units::components::UnitSelectBorder;
+use targeting::TargetingPlugin;
+use weapons::WeaponsPlugin;
+
+pub struct CombatPlugin;
+
+impl Plugin for CombatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((WeaponsPlugin, TargetingPlugin))
+            .add_observer(obs_damage)
+            .add_observer(obs_death);
+    }
+}
+
+fn obs_damage(trigger: Trigger<DamageEv>, mut cmds: Commands, mut q_health: Query<&mut Health>) {
+    let DamageEv { target, dmg } = *trigger.event();
+
+    let Ok(mut health) = q_health.get_mut(target) else {
+        return;
+    };
+
+    // already dead, waiting to be despawned
+    if health.is_dead() {
+        return;
+    }
+
+    health.current = (health.current - dmg).max(0);
+
+    if health.is_dead() {
+        cmds.trigger(DeathEv(target));
+    }
+}
+
+fn obs_death(
+    trigger: Trigger<DeathEv>,
+    mut cmds: Commands,
+    q_border: Query<(Entity, &UnitSelectBorder)>,
+    q_name: Query<&Name>,
+    dbg: Res<DbgOptions>,
+) {
+    let dead_ent = trigger.0;
+
+    if let Ok(name) = q_name.get(dead_ent) {
+        dbg.print(&format!("{} destroyed", name));
+    }
+
+    // remove the selection border that belongs to the dead unit
+    for (border_ent, border) in q_border.iter() {
+        if border.0 == dead_ent {
+            cmds.entity(border_ent).despawn();
+        }
+    }
+
+    cmds.entity(dead_ent).try_despawn();
+}
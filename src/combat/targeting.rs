@@ -0,0 +1,216 @@
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_timer;
+use bevy_rapier3d::plugin::ReadRapierContext;
+use bevy_rapier3d::prelude::Velocity;
+use bevy_rts_pathfinding::components as pf_comps;
+use std::time::Duration;
+
+use super::components::*;
+use crate::fog_of_war::FogOfWar;
+use crate::structures::components::Unpowered;
+use crate::teams::Team;
+use crate::units::components::{HoldPosition, MoveTo, Speed};
+use crate::units::steer_towards;
+use crate::utils;
+
+const CHASE_DIST: f32 = 40.0; // how far past weapon range an idle unit follows its target
+
+pub struct TargetingPlugin;
+
+impl Plugin for TargetingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                acquire_targets.run_if(on_timer(Duration::from_millis(250))),
+                drop_out_of_range_targets,
+                chase_targets,
+                stop_chasing,
+                pursue_attack_orders,
+            ),
+        );
+    }
+}
+
+// idle armed entities pick the closest enemy inside their weapon range that their team can see.
+// artillery outranges its own sight, it needs someone else spotting for it
+fn acquire_targets(
+    mut cmds: Commands,
+    read_rapier: ReadRapierContext,
+    fog: Res<FogOfWar>,
+    q_idle: Query<
+        (Entity, &Transform, &Weapon, &Team),
+        (
+            Without<Target>,
+            Without<AttackOrder>,
+            Without<pf_comps::Destination>,
+            Without<Unpowered>,
+        ),
+    >,
+    q_other: Query<(&Transform, &Team, &Health)>,
+) {
+    let Ok(rapier_ctx) = read_rapier.single() else {
+        return;
+    };
+
+    for (ent, trans, weapon, team) in q_idle.iter() {
+        let pos = trans.translation;
+
+        let nearest = utils::overlap_sphere(&rapier_ctx, pos, weapon.range)
+            .into_iter()
+            .filter_map(|other_ent| {
+                let (other_trans, other_team, health) = q_other.get(other_ent).ok()?;
+                if !team.is_enemy(other_team) || health.is_dead() {
+                    return None;
+                }
+
+                if !fog.is_visible(team, other_trans.translation) {
+                    return None;
+                }
+
+                Some((other_ent, pos.distance_squared(other_trans.translation)))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+
+        if let Some((target_ent, _)) = nearest {
+            cmds.entity(ent).insert(Target(target_ent));
+        }
+    }
+}
+
+// automatically acquired targets are let go once they leave range (or slip into the fog),
+// ordered ones are chased. idle units give a target that backs off a little more room, unless
+// they're holding position
+fn drop_out_of_range_targets(
+    mut cmds: Commands,
+    fog: Res<FogOfWar>,
+    q_armed: Query<
+        (
+            Entity,
+            &Transform,
+            &Weapon,
+            &Target,
+            &Team,
+            Has<Speed>,
+            Has<HoldPosition>,
+        ),
+        Without<AttackOrder>,
+    >,
+    q_target: Query<&GlobalTransform>,
+) {
+    for (ent, trans, weapon, target, team, is_mobile, is_holding) in q_armed.iter() {
+        let Ok(target_trans) = q_target.get(target.0) else {
+            continue;
+        };
+
+        let reach = match is_mobile && !is_holding {
+            true => weapon.range + CHASE_DIST,
+            false => weapon.range,
+        };
+
+        let target_pos = target_trans.translation();
+        let in_reach = trans.translation.xz().distance_squared(target_pos.xz()) <= reach * reach;
+        if !in_reach || !fog.is_visible(team, target_pos) {
+            cmds.entity(ent).remove::<Target>();
+        }
+    }
+}
+
+// idle units close back in on an automatically acquired target that moved out of range
+fn chase_targets(
+    mut q_unit: Query<
+        (&mut Transform, &mut Velocity, &Speed, &Weapon, &Target),
+        (
+            Without<AttackOrder>,
+            Without<HoldPosition>,
+            Without<pf_comps::Destination>,
+            Without<MoveTo>,
+        ),
+    >,
+    q_target: Query<&GlobalTransform>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+
+    for (mut trans, mut vel, speed, weapon, target) in q_unit.iter_mut() {
+        let Ok(target_trans) = q_target.get(target.0) else {
+            continue;
+        };
+
+        let target_pos = target_trans.translation();
+        if weapon.in_range(trans.translation, target_pos) {
+            vel.linvel = Vec3::ZERO;
+            continue;
+        }
+
+        let mut steering = target_pos - trans.translation;
+        steering.y = 0.0;
+        steer_towards(&mut trans, &mut vel, steering, speed.0, dt);
+    }
+}
+
+// a chase ends wherever the target was lost
+fn stop_chasing(
+    mut removed: RemovedComponents<Target>,
+    mut q_vel: Query<
+        &mut Velocity,
+        (
+            Without<AttackOrder>,
+            Without<pf_comps::Destination>,
+            Without<MoveTo>,
+        ),
+    >,
+) {
+    for ent in removed.read() {
+        if let Ok(mut vel) = q_vel.get_mut(ent) {
+            vel.linvel = Vec3::ZERO;
+        }
+    }
+}
+
+fn pursue_attack_orders(
+    mut cmds: Commands,
+    mut q_unit: Query<(
+        Entity,
+        &mut Transform,
+        &mut Velocity,
+        &Speed,
+        &Weapon,
+        &AttackOrder,
+        Has<pf_comps::Destination>,
+    )>,
+    q_target: Query<(&GlobalTransform, &Health)>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+
+    for (ent, mut trans, mut vel, speed, weapon, order, has_destination) in q_unit.iter_mut() {
+        let target = q_target.get(order.0).ok().filter(|(_, h)| !h.is_dead());
+
+        // target destroyed, the order is complete
+        let Some((target_trans, _)) = target else {
+            vel.linvel = Vec3::ZERO;
+            cmds.entity(ent)
+                .try_remove::<(AttackOrder, Target, pf_comps::Destination)>();
+            continue;
+        };
+
+        let target_pos = target_trans.translation();
+
+        if weapon.in_range(trans.translation, target_pos) {
+            vel.linvel = Vec3::ZERO;
+            if has_destination {
+                cmds.entity(ent).remove::<pf_comps::Destination>();
+            }
+            continue;
+        }
+
+        // the flow field only leads to where the target was when the order was given,
+        // once that is used up drive straight at it
+        if !has_destination {
+            let mut steering = target_pos - trans.translation;
+            steering.y = 0.0;
+            steer_towards(&mut trans, &mut vel, steering, speed.0, dt);
+        }
+    }
+}
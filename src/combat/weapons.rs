@@ -0,0 +1,325 @@
+use bevy::prelude::*;
+use bevy::scene::SceneInstanceReady;
+use bevy_rapier3d::plugin::ReadRapierContext;
+use bevy_rapier3d::prelude::*;
+
+use super::components::*;
+use super::events::DamageEv;
+use crate::structures::components::{StructureType, Unpowered};
+use crate::teams::Team;
+use crate::units::components::{IsMoving, UnitType};
+use crate::utils;
+
+const GRAVITY: f32 = 40.0;
+const SPLASH_RADIUS: f32 = 12.0;
+const AIM_TOLERANCE: f32 = 0.15; // radians
+const TURN_SPEED: f32 = 4.0; // radians/sec
+
+pub struct WeaponsPlugin;
+
+impl Plugin for WeaponsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_projectile_assets)
+            .add_systems(
+                Update,
+                (
+                    tick_weapon_cooldowns,
+                    clear_invalid_targets,
+                    fire_weapons.after(tick_weapon_cooldowns),
+                    aim_turrets.after(fire_weapons),
+                    move_projectiles,
+                ),
+            )
+            .add_observer(add_unit_weapon)
+            .add_observer(obs_find_turret_node);
+    }
+}
+
+#[derive(Resource)]
+struct ProjectileAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+#[derive(Component)]
+struct Projectile {
+    shooter: Entity,
+    team: Team,
+    dmg: i32,
+    velocity: Vec3,
+    trajectory: Trajectory,
+    lifetime: Timer,
+}
+
+fn load_projectile_assets(
+    mut cmds: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    cmds.insert_resource(ProjectileAssets {
+        mesh: meshes.add(Sphere::new(0.6)),
+        material: materials.add(StandardMaterial {
+            base_color: Color::srgb(1.0, 0.85, 0.4),
+            emissive: LinearRgba::rgb(4.0, 2.5, 0.8),
+            unlit: true,
+            ..default()
+        }),
+    });
+}
+
+// only armed units get a weapon, harvesters go without
+fn add_unit_weapon(
+    trigger: Trigger<OnAdd, UnitType>,
+    mut cmds: Commands,
+    q_unit: Query<&UnitType>,
+) {
+    let Ok(unit_type) = q_unit.get(trigger.target()) else {
+        return;
+    };
+
+    if let Some(weapon) = unit_type.weapon() {
+        cmds.entity(trigger.target()).insert(weapon);
+    }
+}
+
+fn tick_weapon_cooldowns(mut q_weapon: Query<&mut Weapon>, time: Res<Time>) {
+    for mut weapon in q_weapon.iter_mut() {
+        weapon.cooldown.tick(time.delta());
+    }
+}
+
+fn clear_invalid_targets(
+    mut cmds: Commands,
+    q_target: Query<(Entity, &Target)>,
+    q_health: Query<&Health>,
+) {
+    for (ent, target) in q_target.iter() {
+        let alive = q_health.get(target.0).is_ok_and(|h| !h.is_dead());
+        if !alive {
+            cmds.entity(ent).try_remove::<Target>();
+        }
+    }
+}
+
+fn fire_weapons(
+    mut cmds: Commands,
+    mut q_shooter: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Weapon,
+            &Target,
+            &Team,
+            Option<&IsMoving>,
+            Option<&mut Turret>,
+        ),
+        Without<Unpowered>,
+    >,
+    q_target_trans: Query<&Transform, Without<Weapon>>,
+    q_armed_target_trans: Query<&GlobalTransform, With<Weapon>>,
+    projectile_assets: Res<ProjectileAssets>,
+    time: Res<Time>,
+) {
+    for (shooter_ent, mut trans, mut weapon, target, team, is_moving, mut turret) in
+        q_shooter.iter_mut()
+    {
+        // armed targets are also in the shooter query, so read their position through the global transform
+        let target_pos = match q_target_trans.get(target.0) {
+            Ok(target_trans) => target_trans.translation,
+            Err(_) => match q_armed_target_trans.get(target.0) {
+                Ok(target_trans) => target_trans.translation(),
+                Err(_) => continue,
+            },
+        };
+
+        if !weapon.in_range(trans.translation, target_pos) {
+            continue;
+        }
+
+        // turn to face the target while standing still. turrets turn on their own, anything
+        // else turns its whole body
+        let moving = is_moving.is_some_and(|m| m.0);
+        let to_target = target_pos - trans.translation;
+        let target_rot = Quat::from_rotation_y(f32::atan2(-to_target.x, -to_target.z));
+        let mut aim = turret
+            .as_ref()
+            .and_then(|turret| turret.aim)
+            .unwrap_or(trans.rotation);
+        let angle_diff = aim.angle_between(target_rot);
+        if !moving && angle_diff > 1e-3 {
+            let t = ((TURN_SPEED * time.delta_secs()) / angle_diff).min(1.0);
+            aim = aim.slerp(target_rot, t);
+
+            match turret.as_mut() {
+                Some(turret) => turret.aim = Some(aim),
+                None => trans.rotation = aim,
+            }
+        }
+
+        if weapon.trajectory == Trajectory::Direct && angle_diff > AIM_TOLERANCE {
+            continue;
+        }
+
+        if !weapon.cooldown.finished() {
+            continue;
+        }
+
+        weapon.cooldown.reset();
+
+        let origin = trans.translation + aim * weapon.muzzle_offset;
+        let velocity = launch_velocity(&weapon, origin, target_pos);
+        let flight_time = origin.distance(target_pos) / weapon.projectile_speed;
+
+        let scale = match weapon.trajectory {
+            Trajectory::Direct => 1.0,
+            Trajectory::Ballistic => 2.0,
+        };
+
+        let projectile = (
+            Projectile {
+                shooter: shooter_ent,
+                team: *team,
+                dmg: weapon.dmg,
+                velocity,
+                trajectory: weapon.trajectory,
+                lifetime: Timer::from_seconds(flight_time * 2.0 + 1.0, TimerMode::Once),
+            },
+            Mesh3d(projectile_assets.mesh.clone()),
+            MeshMaterial3d(projectile_assets.material.clone()),
+            Transform::from_translation(origin).with_scale(Vec3::splat(scale)),
+            Name::new("Projectile"),
+        );
+
+        cmds.spawn(projectile);
+    }
+}
+
+// the turret node is looked up again whenever the scene is swapped (placeholder to model)
+fn obs_find_turret_node(
+    trigger: Trigger<SceneInstanceReady>,
+    mut cmds: Commands,
+    q_structure: Query<&StructureType>,
+    q_children: Query<&Children>,
+    q_name: Query<&Name>,
+) {
+    let scene_ent = trigger.target();
+
+    let Some(node_name) = q_structure
+        .get(scene_ent)
+        .ok()
+        .and_then(|structure| structure.turret_node())
+    else {
+        return;
+    };
+
+    let node = q_children.iter_descendants(scene_ent).find(|child| {
+        q_name
+            .get(*child)
+            .is_ok_and(|name| name.as_str() == node_name)
+    });
+
+    if let Some(node) = node {
+        cmds.entity(scene_ent).insert(TurretNode(node));
+    }
+}
+
+// turns the turret node to the aim, relative to the structure it sits on
+fn aim_turrets(
+    q_turret: Query<(&Transform, &Turret, &TurretNode)>,
+    mut q_node: Query<&mut Transform, Without<Turret>>,
+) {
+    for (trans, turret, turret_node) in q_turret.iter() {
+        let Some(aim) = turret.aim else {
+            continue;
+        };
+
+        if let Ok(mut node_trans) = q_node.get_mut(turret_node.0) {
+            node_trans.rotation = trans.rotation.inverse() * aim;
+        }
+    }
+}
+
+fn launch_velocity(weapon: &Weapon, origin: Vec3, target: Vec3) -> Vec3 {
+    let speed = weapon.projectile_speed;
+
+    match weapon.trajectory {
+        Trajectory::Direct => (target - origin).normalize_or_zero() * speed,
+        Trajectory::Ballistic => {
+            // fixed horizontal speed, solve the vertical launch speed so the arc lands on the target
+            let horizontal = Vec3::new(target.x - origin.x, 0.0, target.z - origin.z);
+            let flight_time = (horizontal.length() / speed).max(0.1);
+            let vy =
+                (target.y - origin.y + 0.5 * GRAVITY * flight_time * flight_time) / flight_time;
+            horizontal.normalize_or_zero() * speed + Vec3::Y * vy
+        }
+    }
+}
+
+fn move_projectiles(
+    mut cmds: Commands,
+    mut q_projectile: Query<(Entity, &mut Transform, &mut Projectile)>,
+    q_health: Query<&Team, With<Health>>,
+    read_rapier: ReadRapierContext,
+    time: Res<Time>,
+) {
+    let Ok(rapier_ctx) = read_rapier.single() else {
+        return;
+    };
+
+    let dt = time.delta_secs();
+
+    for (projectile_ent, mut trans, mut projectile) in q_projectile.iter_mut() {
+        if projectile.lifetime.tick(time.delta()).finished() {
+            cmds.entity(projectile_ent).despawn();
+            continue;
+        }
+
+        if projectile.trajectory == Trajectory::Ballistic {
+            projectile.velocity.y -= GRAVITY * dt;
+        }
+
+        let start = trans.translation;
+        let step = projectile.velocity * dt;
+
+        // sweep the segment travelled this frame, so fast projectiles can't tunnel through a collider
+        let filter = QueryFilter::default()
+            .exclude_sensors()
+            .exclude_collider(projectile.shooter);
+        let hit = rapier_ctx.cast_ray(start, step, 1.0, true, filter);
+
+        let mut impact = None;
+        if let Some((hit_ent, toi)) = hit {
+            impact = Some(start + step * toi);
+
+            let is_enemy = q_health
+                .get(hit_ent)
+                .is_ok_and(|team| team.is_enemy(&projectile.team));
+
+            if projectile.trajectory == Trajectory::Direct && is_enemy {
+                cmds.trigger(DamageEv::new(hit_ent, projectile.dmg));
+            }
+        } else if start.y + step.y <= 0.0 {
+            impact = Some(Vec3::new(start.x + step.x, 0.0, start.z + step.z));
+        }
+
+        let Some(impact) = impact else {
+            trans.translation += step;
+            continue;
+        };
+
+        // ballistic shells damage everything around the point of impact
+        if projectile.trajectory == Trajectory::Ballistic {
+            for ent in utils::overlap_sphere(&rapier_ctx, impact, SPLASH_RADIUS) {
+                let is_enemy = q_health
+                    .get(ent)
+                    .is_ok_and(|team| team.is_enemy(&projectile.team));
+
+                if is_enemy {
+                    cmds.trigger(DamageEv::new(ent, projectile.dmg));
+                }
+            }
+        }
+
+        cmds.entity(projectile_ent).despawn();
+    }
+}
@@ -0,0 +1,154 @@
+use bevy::prelude::*;
+use bevy_rts_camera::RtsCamera;
+
+use crate::events::{DeselectAllUnitsEv, SelectUnitsEv};
+use crate::game_state::GameState;
+use crate::units::components::{SelectedUnit, Unit, UnitSelectBorder};
+
+const GROUP_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+const DOUBLE_TAP_SECS: f32 = 0.3;
+const CLR_BADGE: Color = Color::srgba(0.0, 0.0, 0.0, 0.75);
+
+pub struct ControlGroupsPlugin;
+
+impl Plugin for ControlGroupsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ControlGroups>().add_systems(
+            Update,
+            (control_group_input, sync_group_badges)
+                .chain()
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+// Ctrl+1..9 stores the selected units, 1..9 selects them again. a unit is only ever in one group
+#[derive(Resource, Default)]
+pub struct ControlGroups {
+    groups: [Vec<Entity>; 9],
+    last_recall: Option<(usize, f32)>, // group and when it was recalled, for double taps
+}
+
+impl ControlGroups {
+    fn assign(&mut self, idx: usize, units: Vec<Entity>) {
+        for group in self.groups.iter_mut() {
+            group.retain(|ent| !units.contains(ent));
+        }
+
+        self.groups[idx] = units;
+    }
+
+    pub fn group_of(&self, unit: Entity) -> Option<usize> {
+        self.groups.iter().position(|group| group.contains(&unit))
+    }
+}
+
+// number shown in the corner of a grouped unit's select border
+#[derive(Component)]
+struct ControlGroupBadge;
+
+fn control_group_input(
+    mut cmds: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time<Real>>,
+    mut groups: ResMut<ControlGroups>,
+    q_selected: Query<Entity, With<SelectedUnit>>,
+    q_unit: Query<&Transform, With<Unit>>,
+    mut q_cam: Query<&mut RtsCamera>,
+) {
+    let Some(idx) = GROUP_KEYS.iter().position(|key| keys.just_pressed(*key)) else {
+        return;
+    };
+
+    if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        let selected: Vec<Entity> = q_selected.iter().collect();
+        groups.assign(idx, selected);
+        groups.last_recall = None;
+        return;
+    }
+
+    // dead units are dropped from the group
+    groups.groups[idx].retain(|ent| q_unit.contains(*ent));
+
+    let group = groups.groups[idx].clone();
+    if group.is_empty() {
+        return;
+    }
+
+    let now = time.elapsed_secs();
+    let is_double_tap = groups
+        .last_recall
+        .is_some_and(|(last_idx, last_time)| last_idx == idx && now - last_time <= DOUBLE_TAP_SECS);
+    groups.last_recall = Some((idx, now));
+
+    // a double tap only moves the camera, the group is already selected by the first tap
+    if is_double_tap {
+        let center = group
+            .iter()
+            .filter_map(|ent| q_unit.get(*ent).ok())
+            .map(|trans| trans.translation)
+            .sum::<Vec3>()
+            / group.len() as f32;
+
+        if let Ok(mut rts_cam) = q_cam.single_mut() {
+            rts_cam.target_focus.translation.x = center.x;
+            rts_cam.target_focus.translation.z = center.z;
+        }
+
+        return;
+    }
+
+    cmds.trigger(DeselectAllUnitsEv);
+    cmds.trigger(SelectUnitsEv(group));
+}
+
+// badges are children of the select borders, so they follow them around and go with them
+fn sync_group_badges(
+    mut cmds: Commands,
+    groups: Res<ControlGroups>,
+    q_border: Query<(Entity, &UnitSelectBorder)>,
+    q_new_border: Query<(), Added<UnitSelectBorder>>,
+    q_badge: Query<Entity, With<ControlGroupBadge>>,
+) {
+    if !groups.is_changed() && q_new_border.is_empty() {
+        return;
+    }
+
+    for badge_ent in q_badge.iter() {
+        cmds.entity(badge_ent).try_despawn();
+    }
+
+    for (border_ent, border) in q_border.iter() {
+        let Some(idx) = groups.group_of(border.0) else {
+            continue;
+        };
+
+        let badge = (
+            ControlGroupBadge,
+            Text::new((idx + 1).to_string()),
+            TextFont::from_font_size(12.0),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(-14.0),
+                top: Val::Px(-14.0),
+                padding: UiRect::horizontal(Val::Px(3.0)),
+                ..default()
+            },
+            BackgroundColor(CLR_BADGE),
+            Pickable::IGNORE,
+            Name::new("Control Group Badge"),
+        );
+
+        cmds.entity(border_ent).with_child(badge);
+    }
+}
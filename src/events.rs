@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use crate::units::components::UnitOrder;
+
 #[derive(Event)]
 pub struct SetBoxCoordsEv;
 
@@ -15,8 +17,13 @@ pub struct HandleDragSelectEv;
 #[derive(Event)]
 pub struct SetDragSelectEv;
 
-#[derive(Event)]
-pub struct SetUnitDestinationEv;
+// a right-drag sends the group to where the drag started, facing the way it was dragged.
+// otherwise the group goes to the cursor, facing the way it is heading
+#[derive(Event, Default)]
+pub struct SetUnitDestinationEv {
+    pub center: Option<Vec3>,
+    pub facing: Option<Vec3>,
+}
 
 #[derive(Event)]
 pub struct SelectSingleUnitEv(pub Entity);
@@ -24,8 +31,37 @@ pub struct SelectSingleUnitEv(pub Entity);
 #[derive(Event)]
 pub struct SelectMultipleUnitEv;
 
+// adds units to the current selection
+#[derive(Event)]
+pub struct SelectUnitsEv(pub Vec<Entity>);
+
+#[derive(Event)]
+pub struct DeselectUnitEv(pub Entity);
+
+// every on-screen unit of the same type as this one
+#[derive(Event)]
+pub struct SelectSameTypeEv(pub Entity);
+
 #[derive(Event)]
 pub struct DeselectAllUnitsEv;
 
 #[derive(Event)]
 pub struct SelectStructureEv(pub Entity);
+
+#[derive(Event)]
+pub struct AttackTargetEv(pub Entity);
+
+// from the hotkeys or the order buttons, patrol and guard still need a click after this
+#[derive(Event)]
+pub struct UnitOrderEv(pub UnitOrder);
+
+// selected units patrol between where they are and this point
+#[derive(Event)]
+pub struct PatrolEv(pub Vec3);
+
+#[derive(Event)]
+pub struct GuardEv(pub Entity);
+
+// selected units attack-move to the point under the cursor
+#[derive(Event)]
+pub struct AttackMoveEv;
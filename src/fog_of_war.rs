@@ -0,0 +1,244 @@
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::time::common_conditions::on_timer;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::structures::components::{Structure, StructureType};
+use crate::teams::Team;
+use crate::units::components::{Unit, UnitType};
+use crate::{CELL_SIZE, MAP_DEPTH, MAP_GRID_COLUMNS, MAP_GRID_ROWS, MAP_WIDTH};
+
+// how dark the overlay is over each cell
+const ALPHA_UNEXPLORED: u8 = 235;
+const ALPHA_EXPLORED: u8 = 140;
+const ALPHA_VISIBLE: u8 = 0;
+
+pub struct FogOfWarPlugin;
+
+impl Plugin for FogOfWarPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FogOfWar>()
+            .add_systems(Startup, spawn_fog_overlay)
+            .add_systems(
+                Update,
+                (
+                    update_vision.run_if(on_timer(Duration::from_millis(100))),
+                    (hide_enemies, update_fog_overlay).run_if(resource_changed::<FogOfWar>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CellVisibility {
+    #[default]
+    Unexplored,
+    Explored,
+    Visible,
+}
+
+// what a single team can see, one cell per pathfinding grid cell
+#[derive(Clone, Debug)]
+pub struct VisionGrid {
+    cells: Vec<CellVisibility>,
+}
+
+impl Default for VisionGrid {
+    fn default() -> Self {
+        let cell_count = (MAP_GRID_COLUMNS * MAP_GRID_ROWS) as usize;
+        Self {
+            cells: vec![CellVisibility::Unexplored; cell_count],
+        }
+    }
+}
+
+impl VisionGrid {
+    // the (column, row) a world position falls into
+    fn cell(pos: Vec3) -> IVec2 {
+        let col = ((pos.x + MAP_WIDTH / 2.0) / CELL_SIZE).floor() as i32;
+        let row = ((pos.z + MAP_DEPTH / 2.0) / CELL_SIZE).floor() as i32;
+        IVec2::new(
+            col.clamp(0, MAP_GRID_COLUMNS - 1),
+            row.clamp(0, MAP_GRID_ROWS - 1),
+        )
+    }
+
+    fn idx(cell: IVec2) -> usize {
+        (cell.y * MAP_GRID_COLUMNS + cell.x) as usize
+    }
+
+    pub fn get(&self, pos: Vec3) -> CellVisibility {
+        self.cells[Self::idx(Self::cell(pos))]
+    }
+
+    pub fn is_visible(&self, pos: Vec3) -> bool {
+        self.get(pos) == CellVisibility::Visible
+    }
+
+    pub fn is_explored(&self, pos: Vec3) -> bool {
+        self.get(pos) != CellVisibility::Unexplored
+    }
+
+    // everything seen last update is remembered as explored
+    fn fade(&mut self) {
+        for cell in self.cells.iter_mut() {
+            if *cell == CellVisibility::Visible {
+                *cell = CellVisibility::Explored;
+            }
+        }
+    }
+
+    fn reveal(&mut self, pos: Vec3, radius: f32) {
+        let center = Self::cell(pos);
+        let reach = (radius / CELL_SIZE).ceil() as i32;
+
+        for row in (center.y - reach).max(0)..=(center.y + reach).min(MAP_GRID_ROWS - 1) {
+            for col in (center.x - reach).max(0)..=(center.x + reach).min(MAP_GRID_COLUMNS - 1) {
+                let offset = IVec2::new(col, row) - center;
+                if offset.length_squared() > reach * reach {
+                    continue;
+                }
+
+                self.cells[Self::idx(IVec2::new(col, row))] = CellVisibility::Visible;
+            }
+        }
+    }
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct FogOfWar(pub HashMap<Team, VisionGrid>);
+
+impl FogOfWar {
+    pub fn is_visible(&self, team: &Team, pos: Vec3) -> bool {
+        self.0.get(team).is_some_and(|grid| grid.is_visible(pos))
+    }
+
+    pub fn is_explored(&self, team: &Team, pos: Vec3) -> bool {
+        self.0.get(team).is_some_and(|grid| grid.is_explored(pos))
+    }
+}
+
+// the darkening laid over the ground, one pixel per grid cell
+#[derive(Component)]
+struct FogOverlay(Handle<Image>);
+
+fn spawn_fog_overlay(
+    mut cmds: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let size = Extent3d {
+        width: MAP_GRID_COLUMNS as u32,
+        height: MAP_GRID_ROWS as u32,
+        depth_or_array_layers: 1,
+    };
+
+    let img = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0, 0, 0, ALPHA_UNEXPLORED],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    let img = images.add(img);
+
+    let material = StandardMaterial {
+        base_color_texture: Some(img.clone()),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    };
+
+    cmds.spawn((
+        FogOverlay(img),
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(MAP_WIDTH, MAP_DEPTH))),
+        MeshMaterial3d(materials.add(material)),
+        Transform::from_xyz(0.0, 0.1, 0.0),
+        Name::new("Fog Overlay"),
+    ));
+}
+
+fn update_vision(
+    mut fog: ResMut<FogOfWar>,
+    q_unit: Query<(&Transform, &Team, &UnitType), With<Unit>>,
+    q_structure: Query<(&Transform, &Team, &StructureType), With<Structure>>,
+) {
+    for grid in fog.0.values_mut() {
+        grid.fade();
+    }
+
+    for (trans, team, unit_type) in q_unit.iter() {
+        let grid = fog.0.entry(*team).or_default();
+        grid.reveal(trans.translation, unit_type.sight());
+    }
+
+    for (trans, team, structure) in q_structure.iter() {
+        let grid = fog.0.entry(*team).or_default();
+        grid.reveal(trans.translation, structure.sight());
+    }
+}
+
+// enemy units need to be in sight, enemy structures stay visible once they have been spotted
+fn hide_enemies(
+    fog: Res<FogOfWar>,
+    mut q_enemy: Query<
+        (&Transform, &Team, &mut Visibility, Has<Structure>),
+        Or<(With<Unit>, With<Structure>)>,
+    >,
+) {
+    let local = Team::local();
+
+    for (trans, team, mut vis, is_structure) in q_enemy.iter_mut() {
+        if !team.is_enemy(&local) {
+            continue;
+        }
+
+        let is_seen = if is_structure {
+            fog.is_explored(&local, trans.translation)
+        } else {
+            fog.is_visible(&local, trans.translation)
+        };
+
+        let new_vis = if is_seen {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+
+        vis.set_if_neq(new_vis);
+    }
+}
+
+fn update_fog_overlay(
+    fog: Res<FogOfWar>,
+    q_overlay: Query<&FogOverlay>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let Ok(overlay) = q_overlay.single() else {
+        return;
+    };
+
+    let Some(grid) = fog.0.get(&Team::local()) else {
+        return;
+    };
+
+    let Some(img) = images.get_mut(&overlay.0) else {
+        return;
+    };
+
+    let Some(data) = img.data.as_mut() else {
+        return;
+    };
+
+    for (pixel, cell) in data.chunks_exact_mut(4).zip(grid.cells.iter()) {
+        pixel[3] = match cell {
+            CellVisibility::Unexplored => ALPHA_UNEXPLORED,
+            CellVisibility::Explored => ALPHA_EXPLORED,
+            CellVisibility::Visible => ALPHA_VISIBLE,
+        };
+    }
+}
@@ -0,0 +1,289 @@
+use bevy::asset::RecursiveDependencyLoadState;
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_timer;
+use bevy::ui::FocusPolicy;
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::asset_manager::models::MyModels;
+use crate::structures::components::Structure;
+use crate::teams::Team;
+use crate::units::components::Unit;
+
+const CLR_MENU_BACKGROUND: Color = Color::srgba(0.05, 0.05, 0.05, 0.85);
+const CLR_MENU_BTN: Color = Color::srgb(0.2, 0.2, 0.2);
+const CLR_MENU_BTN_HVR: Color = Color::srgb(0.3, 0.3, 0.3);
+const CLR_VICTORY: Color = Color::srgb(0.3, 0.85, 0.3);
+const CLR_DEFEAT: Color = Color::srgb(0.9, 0.2, 0.2);
+
+pub struct GameStatePlugin {
+    pub headless: bool,
+}
+
+impl Plugin for GameStatePlugin {
+    fn build(&self, app: &mut App) {
+        // nobody is around to click through the menu in a headless match
+        let initial_state = if self.headless {
+            GameState::Loading
+        } else {
+            GameState::MainMenu
+        };
+
+        app.insert_state(initial_state)
+            .enable_state_scoped_entities::<GameState>()
+            .init_resource::<EstablishedTeams>()
+            .add_systems(OnEnter(GameState::MainMenu), spawn_main_menu)
+            .add_systems(OnEnter(GameState::Loading), spawn_loading_screen)
+            .add_systems(OnEnter(GameState::Paused), (pause_time, spawn_pause_menu))
+            .add_systems(OnExit(GameState::Paused), resume_time)
+            .add_systems(OnEnter(GameState::GameOver), spawn_game_over_screen)
+            .add_systems(
+                Update,
+                (
+                    menu_btn_interaction,
+                    check_assets_loaded.run_if(in_state(GameState::Loading)),
+                    toggle_pause
+                        .run_if(in_state(GameState::InGame).or(in_state(GameState::Paused))),
+                    check_match_over.run_if(
+                        in_state(GameState::InGame).and(on_timer(Duration::from_millis(500))),
+                    ),
+                ),
+            );
+    }
+}
+
+#[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GameState {
+    #[default]
+    MainMenu,
+    Loading,
+    InGame,
+    Paused,
+    GameOver,
+}
+
+// how the match ended for the local team
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub enum MatchResult {
+    Victory,
+    Defeat,
+}
+
+// teams that have had a base. once it is gone they're out, whatever units they have left
+#[derive(Resource, Default)]
+struct EstablishedTeams(HashSet<Team>);
+
+#[derive(Component, Clone, Copy)]
+enum MenuBtn {
+    Start,
+    Resume,
+    Quit,
+}
+
+impl MenuBtn {
+    fn txt(&self) -> &str {
+        match self {
+            MenuBtn::Start => "Start",
+            MenuBtn::Resume => "Resume",
+            MenuBtn::Quit => "Quit",
+        }
+    }
+}
+
+// full screen overlay, blocking clicks from reaching the game underneath
+fn menu_screen(state: GameState) -> impl Bundle {
+    (
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            row_gap: Val::Px(15.0),
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        BackgroundColor(CLR_MENU_BACKGROUND),
+        FocusPolicy::Block,
+        GlobalZIndex(1000),
+        StateScoped(state),
+        Name::new(format!("{:?} Screen", state)),
+    )
+}
+
+fn menu_title(txt: &str, color: Color) -> impl Bundle {
+    (
+        Text::new(txt),
+        TextFont::from_font_size(60.0),
+        TextColor(color),
+        Node {
+            margin: UiRect::bottom(Val::Px(20.0)),
+            ..default()
+        },
+    )
+}
+
+fn menu_btn(btn: MenuBtn) -> impl Bundle {
+    (
+        Button,
+        btn,
+        Node {
+            width: Val::Px(200.0),
+            padding: UiRect::all(Val::Px(10.0)),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BackgroundColor(CLR_MENU_BTN),
+        Name::new(format!("{} Btn", btn.txt())),
+        children![(Text::new(btn.txt()), TextFont::from_font_size(24.0))],
+    )
+}
+
+fn spawn_main_menu(mut cmds: Commands) {
+    cmds.spawn(menu_screen(GameState::MainMenu))
+        .with_children(|p| {
+            p.spawn(menu_title("RTS", Color::WHITE));
+            p.spawn(menu_btn(MenuBtn::Start));
+            p.spawn(menu_btn(MenuBtn::Quit));
+        });
+}
+
+fn spawn_loading_screen(mut cmds: Commands) {
+    cmds.spawn(menu_screen(GameState::Loading))
+        .with_child(menu_title("Loading...", Color::WHITE));
+}
+
+fn spawn_pause_menu(mut cmds: Commands) {
+    cmds.spawn(menu_screen(GameState::Paused))
+        .with_children(|p| {
+            p.spawn(menu_title("Paused", Color::WHITE));
+            p.spawn(menu_btn(MenuBtn::Resume));
+            p.spawn(menu_btn(MenuBtn::Quit));
+        });
+}
+
+fn spawn_game_over_screen(mut cmds: Commands, result: Res<MatchResult>) {
+    let (txt, color) = match *result {
+        MatchResult::Victory => ("Victory", CLR_VICTORY),
+        MatchResult::Defeat => ("Defeat", CLR_DEFEAT),
+    };
+
+    cmds.spawn(menu_screen(GameState::GameOver))
+        .with_children(|p| {
+            p.spawn(menu_title(txt, color));
+            p.spawn(menu_btn(MenuBtn::Quit));
+        });
+}
+
+fn menu_btn_interaction(
+    mut q_btn: Query<(&Interaction, &MenuBtn, &mut BackgroundColor), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut app_exit: EventWriter<AppExit>,
+) {
+    for (interaction, btn, mut bg_clr) in q_btn.iter_mut() {
+        match interaction {
+            Interaction::None => bg_clr.0 = CLR_MENU_BTN,
+            Interaction::Hovered => bg_clr.0 = CLR_MENU_BTN_HVR,
+            Interaction::Pressed => match btn {
+                MenuBtn::Start => next_state.set(GameState::Loading),
+                MenuBtn::Resume => next_state.set(GameState::InGame),
+                MenuBtn::Quit => {
+                    app_exit.write(AppExit::Success);
+                }
+            },
+        }
+    }
+}
+
+// the match starts once every unit and structure model is ready. failed loads don't hold it up
+fn check_assets_loaded(
+    assets: Res<AssetServer>,
+    my_models: Res<MyModels>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let models = [
+        &my_models.rifleman,
+        &my_models.tank_gen1,
+        &my_models.tank_gen2,
+        &my_models.artillery,
+        &my_models.harvester,
+        &my_models.barracks,
+        &my_models.cannon,
+        &my_models.vehicle_depot,
+        &my_models.research_center,
+        &my_models.satellite_dish,
+        &my_models.refinery,
+        &my_models.power_plant,
+    ];
+
+    let is_loaded = models.iter().all(|model| {
+        matches!(
+            assets.get_recursive_dependency_load_state(*model),
+            Some(RecursiveDependencyLoadState::Loaded | RecursiveDependencyLoadState::Failed(_))
+        )
+    });
+
+    if is_loaded {
+        next_state.set(GameState::InGame);
+    }
+}
+
+fn toggle_pause(
+    keys: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !keys.just_pressed(KeyCode::Escape) {
+        return;
+    }
+
+    match state.get() {
+        GameState::InGame => next_state.set(GameState::Paused),
+        GameState::Paused => next_state.set(GameState::InGame),
+        _ => (),
+    }
+}
+
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+// a team is out once the base it had is destroyed, or once it has nothing left at all
+fn check_match_over(
+    mut cmds: Commands,
+    mut established: ResMut<EstablishedTeams>,
+    mut next_state: ResMut<NextState<GameState>>,
+    q_structure: Query<&Team, With<Structure>>,
+    q_unit: Query<&Team, With<Unit>>,
+) {
+    let is_defeated = |team: Team, established: &mut EstablishedTeams| {
+        let has_structures = q_structure.iter().any(|t| *t == team);
+        let has_units = q_unit.iter().any(|t| *t == team);
+
+        if has_structures {
+            established.0.insert(team);
+        }
+
+        !has_structures && (!has_units || established.0.contains(&team))
+    };
+
+    let local = Team::local();
+    let result = if is_defeated(local, &mut established) {
+        MatchResult::Defeat
+    } else if [Team::Player, Team::Enemy]
+        .into_iter()
+        .filter(|team| team.is_enemy(&local))
+        .all(|team| is_defeated(team, &mut established))
+    {
+        MatchResult::Victory
+    } else {
+        return;
+    };
+
+    cmds.insert_resource(result);
+    next_state.set(GameState::GameOver);
+}
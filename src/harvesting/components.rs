@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use std::time::Duration;
+
+// a patch of harvestable resources on the map, despawned once it runs dry
+#[derive(Component)]
+pub struct ResourceField {
+    pub amount: i32,
+}
+
+impl ResourceField {
+    pub fn new(amount: i32) -> Self {
+        Self { amount }
+    }
+
+    // takes up to 'amount' out of the field, returning what was actually taken
+    pub fn take(&mut self, amount: i32) -> i32 {
+        let taken = amount.min(self.amount);
+        self.amount -= taken;
+        taken
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.amount <= 0
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum HarvestState {
+    #[default]
+    ToField,
+    Gathering,
+    ToRefinery,
+}
+
+#[derive(Component)]
+pub struct Harvester {
+    pub cargo: i32,
+    pub field: Option<Entity>,
+    pub state: HarvestState,
+    pub gather_timer: Timer,
+}
+
+impl Default for Harvester {
+    fn default() -> Self {
+        Self {
+            cargo: 0,
+            field: None,
+            state: HarvestState::default(),
+            gather_timer: Timer::new(Duration::from_secs(1), TimerMode::Repeating),
+        }
+    }
+}
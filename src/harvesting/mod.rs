@@ -0,0 +1,259 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use bevy_rts_pathfinding::components::{self as pf_comps};
+
+pub mod components;
+
+use components::*;
+
+use crate::bank::DepositFundsEv;
+use crate::events::SetUnitDestinationEv;
+use crate::resources::MouseCoords;
+use crate::structures::components::{Structure, StructureType, UnderConstruction};
+use crate::teams::Team;
+use crate::units::components::{
+    Deploying, Guard, HoldPosition, MoveTo, Patrol, SelectedUnit, UnitType,
+};
+use crate::{MAP_DEPTH, MAP_WIDTH};
+
+const FIELD_AMOUNT: i32 = 5000;
+const FIELD_RADIUS: f32 = 20.0;
+const GATHER_AMOUNT: i32 = 50;
+const CARGO_CAPACITY: i32 = 500;
+const DOCK_OFFSET: f32 = 25.0; // distance in front of the refinery where harvesters unload
+const DOCK_RADIUS: f32 = 15.0;
+
+pub struct HarvestingPlugin;
+
+impl Plugin for HarvestingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_resource_fields)
+            .add_systems(Update, harvest.run_if(any_with_component::<Harvester>))
+            .add_observer(add_harvester)
+            .add_observer(set_harvest_field);
+    }
+}
+
+fn spawn_resource_fields(
+    mut cmds: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let base_mesh = meshes.add(Cylinder::new(FIELD_RADIUS, 0.2));
+    let base_material = materials.add(Color::srgb(0.55, 0.45, 0.2));
+    let crystal_mesh = meshes.add(Cuboid::new(2.0, 4.0, 2.0));
+    let crystal_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.95, 0.8, 0.2),
+        emissive: LinearRgba::rgb(0.4, 0.3, 0.0),
+        ..default()
+    });
+
+    // one field near each corner of the map, and a contested one in the middle
+    let (x, z) = (MAP_WIDTH / 3.0, MAP_DEPTH / 3.0);
+    let positions = [
+        Vec3::new(-x, 0.0, -z),
+        Vec3::new(x, 0.0, -z),
+        Vec3::new(-x, 0.0, z),
+        Vec3::new(x, 0.0, z),
+        Vec3::ZERO,
+    ];
+
+    for pos in positions {
+        let field = (
+            ResourceField::new(FIELD_AMOUNT),
+            Mesh3d(base_mesh.clone()),
+            MeshMaterial3d(base_material.clone()),
+            Transform::from_translation(pos),
+            Name::new("Resource Field"),
+        );
+
+        cmds.spawn(field).with_children(|p| {
+            for i in 0..8 {
+                let angle = i as f32 * std::f32::consts::TAU / 8.0;
+                let offset = Vec3::new(angle.cos(), 0.0, angle.sin()) * FIELD_RADIUS * 0.6;
+
+                p.spawn((
+                    Mesh3d(crystal_mesh.clone()),
+                    MeshMaterial3d(crystal_material.clone()),
+                    Transform::from_translation(offset + Vec3::Y * 2.0)
+                        .with_rotation(Quat::from_rotation_y(angle)),
+                    Name::new("Resource Crystal"),
+                ));
+            }
+        });
+    }
+}
+
+fn add_harvester(trigger: Trigger<OnAdd, UnitType>, mut cmds: Commands, q_unit: Query<&UnitType>) {
+    let Ok(unit_type) = q_unit.get(trigger.target()) else {
+        return;
+    };
+
+    if *unit_type == UnitType::Harvester {
+        cmds.entity(trigger.target()).insert(Harvester::default());
+    }
+}
+
+// right clicking a field sends the selected harvesters to work it
+fn set_harvest_field(
+    trigger: Trigger<SetUnitDestinationEv>,
+    mouse_coords: Res<MouseCoords>,
+    q_field: Query<(Entity, &Transform), With<ResourceField>>,
+    mut q_harvester: Query<&mut Harvester, With<SelectedUnit>>,
+) {
+    let dest = trigger.center.unwrap_or(mouse_coords.world);
+    let clicked_field = q_field
+        .iter()
+        .find(|(_, trans)| trans.translation.xz().distance(dest.xz()) <= FIELD_RADIUS);
+
+    let Some((field_ent, _)) = clicked_field else {
+        return;
+    };
+
+    for mut harvester in q_harvester.iter_mut() {
+        harvester.field = Some(field_ent);
+        harvester.state = HarvestState::ToField;
+    }
+}
+
+fn harvest(
+    mut cmds: Commands,
+    time: Res<Time>,
+    mut q_harvester: Query<
+        (
+            Entity,
+            &Transform,
+            &Team,
+            &mut Velocity,
+            &mut Harvester,
+            Has<pf_comps::Destination>,
+            Has<MoveTo>,
+        ),
+        (
+            Without<Deploying>,
+            Without<HoldPosition>,
+            Without<Patrol>,
+            Without<Guard>,
+        ),
+    >,
+    mut q_field: Query<(Entity, &Transform, &mut ResourceField)>,
+    q_refinery: Query<
+        (&Transform, &StructureType, &Team),
+        (With<Structure>, Without<UnderConstruction>),
+    >,
+) {
+    for (harvester_ent, trans, team, mut vel, mut harvester, has_destination, is_moving_to) in
+        q_harvester.iter_mut()
+    {
+        // player move orders come first, harvesting picks back up once they're done
+        if has_destination {
+            continue;
+        }
+
+        let pos = trans.translation;
+
+        match harvester.state {
+            HarvestState::ToField => {
+                // keep working the same field, or move on to the nearest one once it runs dry
+                let field = harvester
+                    .field
+                    .filter(|field_ent| q_field.contains(*field_ent))
+                    .or_else(|| {
+                        q_field
+                            .iter()
+                            .min_by(|(_, a, _), (_, b, _)| {
+                                let a = a.translation.distance_squared(pos);
+                                let b = b.translation.distance_squared(pos);
+                                a.total_cmp(&b)
+                            })
+                            .map(|(field_ent, _, _)| field_ent)
+                    });
+
+                harvester.field = field;
+
+                let Some(Ok((_, field_trans, _))) = field.map(|field_ent| q_field.get(field_ent))
+                else {
+                    // nothing left to harvest, bring back whatever is on board
+                    if harvester.cargo > 0 {
+                        harvester.state = HarvestState::ToRefinery;
+                    }
+                    continue;
+                };
+
+                if pos.xz().distance(field_trans.translation.xz()) <= FIELD_RADIUS {
+                    vel.linvel = Vec3::ZERO;
+                    cmds.entity(harvester_ent).remove::<MoveTo>();
+                    harvester.state = HarvestState::Gathering;
+                    harvester.gather_timer.reset();
+                } else if !is_moving_to {
+                    cmds.entity(harvester_ent)
+                        .insert(MoveTo(field_trans.translation));
+                }
+            }
+            HarvestState::Gathering => {
+                let Some(Ok((field_ent, field_trans, mut field))) =
+                    harvester.field.map(|field_ent| q_field.get_mut(field_ent))
+                else {
+                    harvester.state = HarvestState::ToField;
+                    continue;
+                };
+
+                // pushed or ordered off the field, it has to drive back before mining any more
+                if pos.xz().distance(field_trans.translation.xz()) > FIELD_RADIUS {
+                    harvester.state = HarvestState::ToField;
+                    continue;
+                }
+
+                if !harvester.gather_timer.tick(time.delta()).just_finished() {
+                    continue;
+                }
+
+                let space = CARGO_CAPACITY - harvester.cargo;
+                harvester.cargo += field.take(GATHER_AMOUNT.min(space));
+
+                if field.is_empty() {
+                    cmds.entity(field_ent).try_despawn();
+                }
+
+                if harvester.cargo >= CARGO_CAPACITY || field.is_empty() {
+                    harvester.state = if harvester.cargo > 0 {
+                        HarvestState::ToRefinery
+                    } else {
+                        HarvestState::ToField
+                    };
+                }
+            }
+            HarvestState::ToRefinery => {
+                let dock = q_refinery
+                    .iter()
+                    .filter(|(_, structure, refinery_team)| {
+                        **structure == StructureType::Refinery && *refinery_team == team
+                    })
+                    .map(|(refinery_trans, _, _)| {
+                        refinery_trans.translation + refinery_trans.forward() * DOCK_OFFSET
+                    })
+                    .min_by(|a, b| a.distance_squared(pos).total_cmp(&b.distance_squared(pos)));
+
+                // no refinery to unload at, wait until one is built
+                let Some(dock) = dock else {
+                    continue;
+                };
+
+                if pos.xz().distance(dock.xz()) <= DOCK_RADIUS {
+                    vel.linvel = Vec3::ZERO;
+                    cmds.entity(harvester_ent).remove::<MoveTo>();
+
+                    cmds.trigger(DepositFundsEv {
+                        team: *team,
+                        amount: harvester.cargo,
+                    });
+
+                    harvester.cargo = 0;
+                    harvester.state = HarvestState::ToField;
+                } else if !is_moving_to {
+                    cmds.entity(harvester_ent).insert(MoveTo(dock));
+                }
+            }
+        }
+    }
+}
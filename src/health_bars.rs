@@ -0,0 +1,228 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_rts_camera::RtsCamera;
+use std::collections::HashSet;
+
+use crate::combat::components::Health;
+use crate::game_state::GameState;
+use crate::structures::components::{SelectedStructure, Structure};
+use crate::teams::Team;
+use crate::units::components::{BorderSize, SelectedUnit, Unit};
+use crate::utils::billboard_sync;
+
+const CLR_HEALTH_BAR_BG: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+const BAR_HEIGHT: f32 = 4.0;
+const BAR_OFFSET: f32 = 8.0; // gap above the top of the object's bounds
+const MIN_SIZE_UNIT: f32 = 13.0; // same clamps as the select borders
+const MIN_SIZE_STRUCTURE: f32 = 20.0;
+
+pub struct HealthBarsPlugin {
+    pub health_bars: HealthBars,
+}
+
+impl Plugin for HealthBarsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<HealthBars>()
+            .insert_resource(self.health_bars)
+            .add_systems(
+                Update,
+                (spawn_health_bars, sync_health_bars)
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+// when health bars are shown over units and structures, set with -healthbars-<always|damaged|selected>
+// and cycled in game from the command interface
+#[derive(Resource, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[reflect(Resource)]
+pub enum HealthBars {
+    Always,
+    #[default]
+    WhenDamaged,
+    WhenSelected,
+}
+
+impl HealthBars {
+    pub fn from_arg(arg: &str) -> Option<Self> {
+        match arg {
+            "-healthbars-always" => Some(HealthBars::Always),
+            "-healthbars-damaged" => Some(HealthBars::WhenDamaged),
+            "-healthbars-selected" => Some(HealthBars::WhenSelected),
+            _ => None,
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            HealthBars::Always => HealthBars::WhenDamaged,
+            HealthBars::WhenDamaged => HealthBars::WhenSelected,
+            HealthBars::WhenSelected => HealthBars::Always,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            HealthBars::Always => "HP: Always",
+            HealthBars::WhenDamaged => "HP: Damaged",
+            HealthBars::WhenSelected => "HP: Selected",
+        }
+    }
+
+    fn shows(&self, health: &Health, is_selected: bool) -> bool {
+        match self {
+            HealthBars::Always => true,
+            HealthBars::WhenDamaged => health.is_damaged(),
+            HealthBars::WhenSelected => is_selected,
+        }
+    }
+}
+
+// covers the object's screen bounds like a select border, with the bar sitting on top of it
+#[derive(Component)]
+struct HealthBar {
+    owner: Entity,
+    fill: Entity,
+}
+
+#[derive(Component)]
+struct HealthBarFill;
+
+fn spawn_health_bars(
+    mut cmds: Commands,
+    health_bars: Res<HealthBars>,
+    q_obj: Query<
+        (
+            Entity,
+            &Health,
+            &Team,
+            Has<SelectedUnit>,
+            Has<SelectedStructure>,
+        ),
+        Or<(With<Unit>, With<Structure>)>,
+    >,
+    q_bar: Query<&HealthBar>,
+) {
+    let has_bar: HashSet<Entity> = q_bar.iter().map(|bar| bar.owner).collect();
+
+    for (ent, health, team, is_selected_unit, is_selected_structure) in q_obj.iter() {
+        let is_selected = is_selected_unit || is_selected_structure;
+        if has_bar.contains(&ent) || !health_bars.shows(health, is_selected) {
+            continue;
+        }
+
+        let fill = cmds
+            .spawn((
+                HealthBarFill,
+                Node {
+                    height: Val::Percent(100.0),
+                    width: Val::Percent(health.fraction() * 100.0),
+                    ..default()
+                },
+                BackgroundColor(team.color()),
+                Pickable::IGNORE,
+                Name::new("Health Bar Fill"),
+            ))
+            .id();
+
+        let bar = (
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(-BAR_OFFSET),
+                width: Val::Percent(100.0),
+                height: Val::Px(BAR_HEIGHT),
+                ..default()
+            },
+            BackgroundColor(CLR_HEALTH_BAR_BG),
+            Pickable::IGNORE,
+            Name::new("Health Bar"),
+        );
+
+        let bar_ent = cmds.spawn(bar).add_child(fill).id();
+
+        cmds.spawn((
+            HealthBar { owner: ent, fill },
+            Node {
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            Pickable::IGNORE,
+            Name::new("Health Bar Ctr"),
+        ))
+        .add_child(bar_ent);
+    }
+}
+
+// follows the object around the screen, and goes once it no longer needs showing
+fn sync_health_bars(
+    mut cmds: Commands,
+    health_bars: Res<HealthBars>,
+    mut q_bar: Query<(Entity, &HealthBar, &mut Node), Without<HealthBarFill>>,
+    mut q_fill: Query<&mut Node, With<HealthBarFill>>,
+    q_obj: Query<(
+        &Transform,
+        &BorderSize,
+        &Health,
+        Option<&Visibility>,
+        Has<Structure>,
+        Has<SelectedUnit>,
+        Has<SelectedStructure>,
+    )>,
+    q_cam: Query<(&Camera, &GlobalTransform), With<RtsCamera>>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+) {
+    let Ok((cam, cam_trans)) = q_cam.single() else {
+        return;
+    };
+
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+
+    for (bar_ent, bar, mut node) in q_bar.iter_mut() {
+        let Ok((
+            trans,
+            border_size,
+            health,
+            vis,
+            is_structure,
+            is_selected_unit,
+            is_selected_structure,
+        )) = q_obj.get(bar.owner)
+        else {
+            cmds.entity(bar_ent).try_despawn();
+            continue;
+        };
+
+        let is_selected = is_selected_unit || is_selected_structure;
+        if !health_bars.shows(health, is_selected) {
+            cmds.entity(bar_ent).try_despawn();
+            continue;
+        }
+
+        // enemies hidden in the fog keep their health to themselves
+        node.display = match vis {
+            Some(Visibility::Hidden) => Display::None,
+            _ => Display::Flex,
+        };
+
+        let min_size = match is_structure {
+            true => MIN_SIZE_STRUCTURE,
+            false => MIN_SIZE_UNIT,
+        };
+        billboard_sync(
+            cam,
+            cam_trans,
+            window,
+            trans,
+            border_size.0,
+            &mut node,
+            min_size,
+        );
+
+        if let Ok(mut fill) = q_fill.get_mut(bar.fill) {
+            fill.width = Val::Percent(health.fraction() * 100.0);
+        }
+    }
+}
@@ -1,35 +1,58 @@
+use bevy::app::ScheduleRunnerPlugin;
 use bevy::prelude::*;
+use bevy::render::{settings::WgpuSettings, RenderPlugin};
+use bevy::window::ExitCondition;
+use bevy::winit::WinitPlugin;
 use bevy_egui::EguiPlugin;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 // use bevy_kira_audio::{AudioPlugin, SpatialAudioPlugin};
 use bevy_mod_outline::OutlinePlugin;
 use bevy_rapier3d::prelude::*;
 use bevy_rts_pathfinding;
+use std::time::Duration;
 
+mod ai;
 mod asset_manager;
 mod bank;
 mod camera;
 mod cmd_interface;
+mod combat;
+mod control_groups;
 mod events;
+mod fog_of_war;
+mod game_state;
+mod harvesting;
+mod health_bars;
 mod map;
 mod mouse;
+mod research;
 mod resources;
 mod shaders;
 mod structures;
 mod tank;
+mod teams;
 mod units;
 mod utils;
 
+use ai::{components::AiDifficulty, AiPlugin};
 use asset_manager::AssetManagerPlugin;
 use bank::BankPlugin;
 use camera::CameraPlugin;
 use cmd_interface::CmdInterfacePlugin;
+use combat::CombatPlugin;
+use control_groups::ControlGroupsPlugin;
+use fog_of_war::FogOfWarPlugin;
+use game_state::GameStatePlugin;
+use harvesting::HarvestingPlugin;
+use health_bars::{HealthBars, HealthBarsPlugin};
 use map::MapPlugin;
 use mouse::MousePlugin;
+use research::ResearchPlugin;
 use resources::ResourcesPlugin;
 use shaders::ShadersPlugin;
 use structures::StructuresPlugin;
 use tank::TankPlugin;
+use teams::TeamsPlugin;
 use units::UnitsPlugin;
 
 // const COLOR_GROUND: Color = Color::srgb(0.44, 0.75, 0.44);
@@ -47,28 +70,55 @@ const SPEED_RIFELMAN: f32 = SPEED_QUANTIFIER * 10.0;
 const SPEED_TANK_GEN_1: f32 = SPEED_QUANTIFIER * 50.0;
 const SPEED_TANK_GEN_2: f32 = SPEED_QUANTIFIER * 50.0;
 const SPEED_ARTILLERY: f32 = SPEED_QUANTIFIER * 40.0;
+const SPEED_HARVESTER: f32 = SPEED_QUANTIFIER * 35.0;
 
 fn main() {
-    // let args: Vec<String> = std::env::args().collect();
-    // let shorts_flag = args.contains(&String::from("-shorts"));
+    let args: Vec<String> = std::env::args().collect();
+    let headless = args.contains(&String::from("-headless"));
+    let difficulty = args
+        .iter()
+        .find_map(|arg| AiDifficulty::from_arg(arg))
+        .unwrap_or_default();
+    let health_bars = args
+        .iter()
+        .find_map(|arg| HealthBars::from_arg(arg))
+        .unwrap_or_default();
 
     let mut app = App::new();
 
     // app.register_type::<bevy_rts_pathfinding::BoidsResource>();
     // app.init_resource::<bevy_rts_pathfinding::BoidsResource>();
 
-    app.add_plugins(DefaultPlugins);
+    if headless {
+        // no window or gpu, the ai plays both sides
+        app.add_plugins((
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    ..default()
+                })
+                .set(RenderPlugin {
+                    render_creation: WgpuSettings {
+                        backends: None,
+                        ..default()
+                    }
+                    .into(),
+                    ..default()
+                })
+                .disable::<WinitPlugin>(),
+            ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(1.0 / 60.0)),
+        ));
+    } else {
+        app.add_plugins(DefaultPlugins);
+    }
+
     app.add_plugins((
         CmdInterfacePlugin,
         AssetManagerPlugin,
         BankPlugin,
         RapierPhysicsPlugin::<NoUserData>::default(),
         // RapierDebugRenderPlugin::default(),
-        EguiPlugin {
-            enable_multipass_for_primary_context: true,
-        },
-        WorldInspectorPlugin::new(),
-        OutlinePlugin,
         bevy_rts_pathfinding::BevyRtsPathFindingPlugin,
         ResourcesPlugin,
         StructuresPlugin,
@@ -79,11 +129,32 @@ fn main() {
         TankPlugin,
     ));
 
-    app.add_plugins(/*AudioPlugin, SpatialAudioPlugin,*/ ShadersPlugin);
+    // plugin tuples max out at 15
+    app.add_plugins((
+        CombatPlugin,
+        ControlGroupsPlugin,
+        HarvestingPlugin,
+        FogOfWarPlugin,
+        ResearchPlugin,
+        TeamsPlugin,
+        HealthBarsPlugin { health_bars },
+        GameStatePlugin { headless },
+        AiPlugin {
+            difficulty,
+            headless,
+        },
+    ));
 
-    // if !shorts_flag {
-    //     app.add_plugins(WorldInspectorPlugin::new());
-    // }
+    if !headless {
+        app.add_plugins((
+            EguiPlugin {
+                enable_multipass_for_primary_context: true,
+            },
+            WorldInspectorPlugin::new(),
+            OutlinePlugin,
+        ));
+        app.add_plugins(/*AudioPlugin, SpatialAudioPlugin,*/ ShadersPlugin);
+    }
 
     app.run();
 }
@@ -3,16 +3,22 @@ use bevy::{prelude::*, window::PrimaryWindow};
 use bevy_rts_camera::RtsCamera;
 use core::f32;
 
+use crate::asset_manager::audio::{AudioCmd, UnitAudioEv};
 use crate::asset_manager::imgs::MyImgs;
 use crate::events::*;
+use crate::game_state::GameState;
 use crate::resources::*;
 use crate::structures::components::Structure;
 use crate::structures::events::DeselectAllStructuresEv;
+use crate::teams::Team;
 use crate::units::components::*;
 use crate::utils::{self, billboard_sync};
 use crate::*;
 use bevy_rts_pathfinding::components::{self as pf_comps};
 
+const DOUBLE_CLICK_SECS: f32 = 0.3;
+const FORMATION_DRAG_MIN: f32 = 10.0; // shorter right-drags are just clicks
+
 pub struct MousePlugin;
 
 impl Plugin for MousePlugin {
@@ -24,15 +30,20 @@ impl Plugin for MousePlugin {
                 (
                     set_is_any_selected,
                     mouse_input,
+                    select_all_units,
                     draw_drag_select_box,
                     set_drag_select,
                     sync_select_border_with_unit,
                     update_cursor_img,
                 )
-                    .chain(),
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
             )
             .add_observer(deselect_all)
             .add_observer(single_select_unit)
+            .add_observer(select_units)
+            .add_observer(deselect_unit)
+            .add_observer(select_same_type)
             .add_observer(handle_drag_select)
             .add_observer(set_start_drag_select_box_coords)
             .add_observer(set_drag_select_box_coords)
@@ -85,18 +96,46 @@ fn spawn_drag_select_box(mut cmds: Commands) {
 
 fn mouse_input(
     mut cmds: Commands,
-    game_cmds: Res<GameCommands>,
+    mut game_cmds: ResMut<GameCommands>,
     input: Res<ButtonInput<MouseButton>>,
     read_rapier: ReadRapierContext,
     q_cam: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
     mouse_coords: Res<MouseCoords>,
-    q_unit: Query<Entity, With<Unit>>,
-    q_structure: Query<Entity, With<Structure>>,
+    q_unit: Query<&Team, With<Unit>>,
+    q_structure: Query<&Team, With<Structure>>,
+    q_vis: Query<&Visibility>,
+    q_selected: Query<(), With<SelectedUnit>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time<Real>>,
+    mut last_click: Local<Option<(Entity, f32)>>,
+    mut right_drag_start: Local<Option<Vec3>>,
 ) {
     if game_cmds.hvr_cmd_interface {
         return;
     }
 
+    // right-dragging with units selected places their formation where the drag starts, facing
+    // the way it goes. like a click, it has to start on open ground (the ground is a sensor, so
+    // any hit is a unit or structure)
+    if input.just_pressed(MouseButton::Right) {
+        let Ok(rapier_ctx) = read_rapier.single() else {
+            return;
+        };
+
+        let Ok((cam, cam_trans)) = q_cam.single() else {
+            return;
+        };
+
+        let on_ground =
+            utils::cast_ray(&rapier_ctx, &cam, &cam_trans, mouse_coords.viewport).is_none();
+
+        *right_drag_start = Some(mouse_coords.world)
+            .filter(|_| game_cmds.is_any_unit_selected && mouse_coords.in_bounds() && on_ground);
+    }
+
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+
     cmds.trigger(SetDragSelectEv);
 
     if input.just_pressed(MouseButton::Left) {
@@ -107,40 +146,105 @@ fn mouse_input(
     if input.pressed(MouseButton::Left) {
         cmds.trigger(SetBoxCoordsEv);
 
-        if game_cmds.drag_select {
+        if game_cmds.drag_select && game_cmds.pending_order.is_none() {
             cmds.trigger(HandleDragSelectEv);
         }
 
         return;
     }
 
-    if input.just_released(MouseButton::Left) {
-        cmds.trigger(ClearBoxCoordsEv);
-
-        if !game_cmds.drag_select {
-            let Ok(rapier_ctx) = read_rapier.single() else {
-                return;
-            };
+    // find what is under the cursor, split into our own objects and enemy objects
+    let mut hit_unit = None;
+    let mut hit_structure = None;
+    let mut hit_enemy = None;
+    if input.just_released(MouseButton::Left) || input.just_released(MouseButton::Right) {
+        let Ok(rapier_ctx) = read_rapier.single() else {
+            return;
+        };
 
-            let Ok((cam, cam_trans)) = q_cam.single() else {
-                return;
-            };
+        let Ok((cam, cam_trans)) = q_cam.single() else {
+            return;
+        };
 
-            let hit = utils::cast_ray(&rapier_ctx, &cam, &cam_trans, mouse_coords.viewport);
+        let hit = utils::cast_ray(&rapier_ctx, &cam, &cam_trans, mouse_coords.viewport);
 
-            let mut hit_unit = None;
-            let mut hit_structure = None;
-            if let Some((hit_ent, _)) = hit {
-                if let Ok(_) = q_unit.get(hit_ent) {
+        if let Some((hit_ent, _)) = hit {
+            if let Ok(team) = q_unit.get(hit_ent).or(q_structure.get(hit_ent)) {
+                if !team.is_local() {
+                    // enemies hidden in the fog can't be targeted
+                    let is_hidden = q_vis
+                        .get(hit_ent)
+                        .is_ok_and(|vis| *vis == Visibility::Hidden);
+                    if !is_hidden {
+                        hit_enemy = Some(hit_ent);
+                    }
+                } else if q_unit.get(hit_ent).is_ok() {
                     hit_unit = Some(hit_ent);
+                } else {
+                    hit_structure = Some(hit_ent);
                 }
+            }
+        }
+    }
 
-                if let Ok(_) = q_structure.get(hit_ent) {
-                    hit_structure = Some(hit_ent);
+    // clicking an enemy while units are selected is an attack order
+    let attack_target = hit_enemy.filter(|_| game_cmds.is_any_unit_selected);
+
+    if input.just_released(MouseButton::Left) {
+        cmds.trigger(ClearBoxCoordsEv);
+
+        // a patrol, guard or attack-move order takes the next click, for where to go or who to guard
+        if let Some(order) = game_cmds.pending_order.take() {
+            match order {
+                UnitOrder::AttackMove => {
+                    if let Some(enemy_ent) = attack_target {
+                        cmds.trigger(AttackTargetEv(enemy_ent));
+                    } else if mouse_coords.in_bounds() {
+                        cmds.trigger(AttackMoveEv);
+                    }
+                }
+                UnitOrder::Patrol if mouse_coords.in_bounds() => {
+                    cmds.trigger(PatrolEv(mouse_coords.world));
                 }
+                UnitOrder::Guard => {
+                    if let Some(guarded_ent) = hit_unit.or(hit_structure) {
+                        cmds.trigger(GuardEv(guarded_ent));
+                    }
+                }
+                _ => (),
             }
 
-            if !game_cmds.is_any_unit_selected || hit_unit.is_some() || hit_structure.is_some() {
+            return;
+        }
+
+        if !game_cmds.drag_select {
+            // a second click on the same unit in quick succession
+            let now = time.elapsed_secs();
+            let is_double_click = hit_unit.is_some_and(|unit_ent| {
+                last_click.is_some_and(|(last_ent, last_time)| {
+                    last_ent == unit_ent && now - last_time <= DOUBLE_CLICK_SECS
+                })
+            });
+            *last_click = hit_unit.filter(|_| !is_double_click).map(|ent| (ent, now));
+
+            // attack orders are right click only, left click just selects
+            if let Some(unit_ent) = hit_unit.filter(|_| is_double_click) {
+                if !shift {
+                    cmds.trigger(DeselectAllUnitsEv);
+                }
+
+                cmds.trigger(SelectSameTypeEv(unit_ent));
+            } else if let Some(unit_ent) = hit_unit.filter(|_| shift || ctrl) {
+                // shift adds the unit to the selection, ctrl toggles it in or out
+                if ctrl && q_selected.contains(unit_ent) {
+                    cmds.trigger(DeselectUnitEv(unit_ent));
+                } else {
+                    cmds.trigger(SelectUnitsEv(vec![unit_ent]));
+                }
+            } else if !game_cmds.is_any_unit_selected
+                || hit_unit.is_some()
+                || hit_structure.is_some()
+            {
                 cmds.trigger(DeselectAllUnitsEv);
 
                 if let Some(hit_ent) = hit_unit {
@@ -153,7 +257,7 @@ fn mouse_input(
                     cmds.trigger(DeselectAllStructuresEv);
                 }
             } else if hit_structure.is_none() {
-                cmds.trigger(SetUnitDestinationEv);
+                cmds.trigger(SetUnitDestinationEv::default());
             }
         } else {
             cmds.trigger(SelectMultipleUnitEv);
@@ -163,7 +267,28 @@ fn mouse_input(
     }
 
     if input.just_released(MouseButton::Right) {
-        cmds.trigger(DeselectAllUnitsEv);
+        let drag = right_drag_start
+            .take()
+            .map(|start| (start, mouse_coords.world - start))
+            .filter(|(_, facing)| facing.xz().length() >= FORMATION_DRAG_MIN);
+
+        // cancels an order that is still waiting on its click
+        if game_cmds.pending_order.take().is_some() {
+            return;
+        }
+
+        // right clicking an enemy attacks it, a drag moves the selected units (to where it
+        // started, facing the way it was dragged), anywhere else clears the selection
+        if let Some(enemy_ent) = attack_target {
+            cmds.trigger(AttackTargetEv(enemy_ent));
+        } else if let Some((start, facing)) = drag {
+            cmds.trigger(SetUnitDestinationEv {
+                center: Some(start),
+                facing: Some(facing),
+            });
+        } else {
+            cmds.trigger(DeselectAllUnitsEv);
+        }
     }
 }
 
@@ -189,9 +314,21 @@ fn set_start_drag_select_box_coords(
     _trigger: Trigger<SetStartBoxCoordsEv>,
     mut box_coords: ResMut<SelectBox>,
     mouse_coords: Res<MouseCoords>,
+    keys: Res<ButtonInput<KeyCode>>,
+    q_selected: Query<Entity, With<SelectedUnit>>,
 ) {
     box_coords.viewport.initialize_coords(mouse_coords.viewport);
     box_coords.world.initialize_coords(mouse_coords.world);
+
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+
+    box_coords.kept = if shift || ctrl {
+        q_selected.iter().collect()
+    } else {
+        Vec::new()
+    };
+    box_coords.toggle = ctrl;
 }
 
 fn set_drag_select_box_coords(
@@ -313,7 +450,7 @@ fn draw_drag_select_box(
 pub fn handle_drag_select(
     _trigger: Trigger<HandleDragSelectEv>,
     mut cmds: Commands,
-    mut q_unit: Query<(Entity, &Transform), With<UnitType>>,
+    mut q_unit: Query<(Entity, &Transform, &Team), With<UnitType>>,
     box_coords: Res<SelectBox>,
     q_selected: Query<&SelectedUnit>,
     my_imgs: Res<MyImgs>,
@@ -338,7 +475,12 @@ pub fn handle_drag_select(
     };
 
     // check to see if units are within selection rectangle
-    for (friendly_ent, friendly_trans) in q_unit.iter_mut() {
+    for (friendly_ent, friendly_trans, team) in q_unit.iter_mut() {
+        // only the local player's units can be selected
+        if !team.is_local() {
+            continue;
+        }
+
         let unit_pos = friendly_trans.translation;
 
         // Calculate cross products for each edge
@@ -363,8 +505,16 @@ pub fn handle_drag_select(
             && cross_da_dp > 0.0)
             || (cross_ab_ap < 0.0 && cross_bc_bp < 0.0 && cross_cd_cp < 0.0 && cross_da_dp < 0.0);
 
+        // shift keeps the old selection around the box, ctrl flips whatever is inside it
+        let was_kept = box_coords.kept.contains(&friendly_ent);
+        let is_selected = if in_box_bounds {
+            !(box_coords.toggle && was_kept)
+        } else {
+            was_kept
+        };
+
         // Set the selection status
-        if in_box_bounds {
+        if is_selected {
             if q_selected.get(friendly_ent).is_err() {
                 cmds.entity(friendly_ent).insert(SelectedUnit);
                 cmds.spawn(border(friendly_ent));
@@ -423,7 +573,9 @@ pub fn update_cursor_img(
     let hit: Option<(Entity, f32)> =
         utils::cast_ray(&rapier_ctx, &cam, &cam_trans, mouse_coords.viewport);
 
-    if hit.is_some()
+    if game_cmds.pending_order == Some(UnitOrder::AttackMove) {
+        *cursor_state = CursorState::AttackMove;
+    } else if hit.is_some()
         && !game_cmds.drag_select
         && *cursor_state != CursorState::Build
         && !game_cmds.hvr_cmd_interface
@@ -457,6 +609,11 @@ pub fn update_cursor_img(
             img = my_imgs.cursor_select.clone();
             hotspot = (25, 25)
         }
+        CursorState::AttackMove => {
+            window.cursor_options.visible = true;
+            img = my_imgs.cursor_attack_move.clone();
+            hotspot = (20, 20)
+        }
         CursorState::Build => {
             window.cursor_options.visible = false;
             img = my_imgs.cursor_relocate.clone();
@@ -513,6 +670,106 @@ pub fn deselect_all(
     }
 }
 
+pub fn select_units(
+    trigger: Trigger<SelectUnitsEv>,
+    mut cmds: Commands,
+    q_selected: Query<(), With<SelectedUnit>>,
+    q_unit_type: Query<&UnitType>,
+    my_imgs: Res<MyImgs>,
+) {
+    for unit_ent in trigger.0.iter().copied() {
+        if q_selected.contains(unit_ent) {
+            continue;
+        }
+
+        cmds.entity(unit_ent).insert(SelectedUnit);
+        cmds.spawn((
+            UnitSelectBorder(unit_ent),
+            ImageNode::new(my_imgs.select_border.clone()),
+            Name::new("Unit Select Border"),
+        ));
+    }
+
+    // a single unit answers for itself, a group with its most common unit, nothing stays quiet
+    match trigger.0[..] {
+        [] => (),
+        [unit_ent] => {
+            if let Ok(unit_type) = q_unit_type.get(unit_ent) {
+                cmds.trigger(UnitAudioEv::new(AudioCmd::Select, *unit_type));
+            }
+        }
+        _ => cmds.trigger(SelectMultipleUnitEv),
+    }
+}
+
+pub fn deselect_unit(
+    trigger: Trigger<DeselectUnitEv>,
+    mut cmds: Commands,
+    q_border: Query<(Entity, &UnitSelectBorder)>,
+) {
+    let unit_ent = trigger.0;
+
+    cmds.entity(unit_ent).remove::<SelectedUnit>();
+
+    for (border_ent, border) in q_border.iter() {
+        if border.0 == unit_ent {
+            cmds.entity(border_ent).despawn();
+        }
+    }
+}
+
+fn select_same_type(
+    trigger: Trigger<SelectSameTypeEv>,
+    mut cmds: Commands,
+    q_unit: Query<(Entity, &Transform, &UnitType, &Team)>,
+    q_cam: Query<(&Camera, &GlobalTransform), With<RtsCamera>>,
+) {
+    let Ok((_, _, unit_type, _)) = q_unit.get(trigger.0) else {
+        return;
+    };
+
+    let Ok((cam, cam_trans)) = q_cam.single() else {
+        return;
+    };
+
+    let Some(screen) = cam.logical_viewport_rect() else {
+        return;
+    };
+
+    let units = q_unit
+        .iter()
+        .filter(|(_, _, other_type, team)| **other_type == *unit_type && team.is_local())
+        .filter(|(_, trans, _, _)| {
+            cam.world_to_viewport(cam_trans, trans.translation)
+                .is_ok_and(|pos| screen.contains(pos))
+        })
+        .map(|(ent, _, _, _)| ent)
+        .collect();
+
+    cmds.trigger(SelectUnitsEv(units));
+}
+
+// Ctrl+A
+fn select_all_units(
+    mut cmds: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    q_unit: Query<(Entity, &Team), With<Unit>>,
+) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if !ctrl || !keys.just_pressed(KeyCode::KeyA) {
+        return;
+    }
+
+    let units = q_unit
+        .iter()
+        .filter(|(_, team)| team.is_local())
+        .map(|(ent, _)| ent)
+        .collect();
+
+    cmds.trigger(DeselectAllUnitsEv);
+    cmds.trigger(SelectUnitsEv(units));
+}
+
 fn set_is_any_selected(
     mut cmds: Commands,
     q_selected: Query<&SelectedUnit>,
@@ -521,5 +778,7 @@ fn set_is_any_selected(
     game_cmds.is_any_unit_selected = q_selected.iter().next().is_some();
     if game_cmds.is_any_unit_selected {
         cmds.trigger(DeselectAllStructuresEv);
+    } else {
+        game_cmds.pending_order = None;
     }
 }
@@ -0,0 +1,129 @@
+use bevy::prelude::*;
+use std::time::Duration;
+use strum_macros::EnumIter;
+
+use crate::combat::components::{Health, Weapon};
+use crate::units::components::UnitType;
+
+// what finishing a research does
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResearchEffect {
+    Unlock(UnitType),
+    HpBonus(f32),
+    DmgBonus(f32),
+    RangeBonus(f32),
+}
+
+#[derive(EnumIter, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ResearchType {
+    TankGen2,
+    CompositeArmor,
+    HighExplosiveShells,
+    AdvancedOptics,
+}
+
+impl ResearchType {
+    pub fn name(&self) -> String {
+        match self {
+            ResearchType::TankGen2 => "Tank Gen II".to_string(),
+            ResearchType::CompositeArmor => "Composite Armor".to_string(),
+            ResearchType::HighExplosiveShells => "HE Shells".to_string(),
+            ResearchType::AdvancedOptics => "Advanced Optics".to_string(),
+        }
+    }
+
+    pub fn cost(&self) -> i32 {
+        match self {
+            ResearchType::TankGen2 => 1500,
+            ResearchType::CompositeArmor => 1000,
+            ResearchType::HighExplosiveShells => 1200,
+            ResearchType::AdvancedOptics => 1000,
+        }
+    }
+
+    // seconds
+    pub fn duration(&self) -> u64 {
+        match self {
+            ResearchType::TankGen2 => 30,
+            ResearchType::CompositeArmor => 25,
+            ResearchType::HighExplosiveShells => 30,
+            ResearchType::AdvancedOptics => 20,
+        }
+    }
+
+    // research that has to be finished before this one can start
+    pub fn prerequisite(&self) -> Option<ResearchType> {
+        match self {
+            ResearchType::TankGen2 => None,
+            ResearchType::CompositeArmor => None,
+            ResearchType::HighExplosiveShells => Some(ResearchType::CompositeArmor),
+            ResearchType::AdvancedOptics => Some(ResearchType::TankGen2),
+        }
+    }
+
+    pub fn effect(&self) -> ResearchEffect {
+        match self {
+            ResearchType::TankGen2 => ResearchEffect::Unlock(UnitType::TankGen2),
+            ResearchType::CompositeArmor => ResearchEffect::HpBonus(0.25),
+            ResearchType::HighExplosiveShells => ResearchEffect::DmgBonus(0.2),
+            ResearchType::AdvancedOptics => ResearchEffect::RangeBonus(0.15),
+        }
+    }
+
+    // stat upgrades scale a unit's stats, unlocks are handled through 'UnlockedUnits'. health and
+    // weapon are upgraded apart, since unarmed units only have the one
+    pub fn upgrade_health(&self, health: &mut Health) {
+        if let ResearchEffect::HpBonus(bonus) = self.effect() {
+            health.max = (health.max as f32 * (1.0 + bonus)).round() as i32;
+            health.current = (health.current as f32 * (1.0 + bonus)).round() as i32;
+        }
+    }
+
+    pub fn upgrade_weapon(&self, weapon: &mut Weapon) {
+        match self.effect() {
+            ResearchEffect::DmgBonus(bonus) => {
+                weapon.dmg = (weapon.dmg as f32 * (1.0 + bonus)).round() as i32;
+            }
+            ResearchEffect::RangeBonus(bonus) => {
+                weapon.range *= 1.0 + bonus;
+            }
+            _ => (),
+        }
+    }
+}
+
+// research waiting at a research center, only the first one is in progress
+#[derive(Component, Default)]
+pub struct ResearchQueue(pub Vec<(ResearchType, Timer)>);
+
+impl ResearchQueue {
+    pub fn push(&mut self, research: ResearchType) {
+        let timer = Timer::new(Duration::from_secs(research.duration()), TimerMode::Once);
+        self.0.push((research, timer));
+    }
+
+    pub fn contains(&self, research: ResearchType) -> bool {
+        self.0.iter().any(|(queued, _)| *queued == research)
+    }
+
+    // removes the queued research and returns the refund
+    pub fn cancel(&mut self, research: ResearchType) -> Option<i32> {
+        let idx = self.0.iter().position(|(queued, _)| *queued == research)?;
+        let (_, timer) = self.0.remove(idx);
+        Some(refund(research, &timer))
+    }
+
+    // what everything still in the queue is worth, for when the research center is destroyed
+    pub fn refund_all(&self) -> i32 {
+        self.0
+            .iter()
+            .map(|(research, timer)| refund(*research, timer))
+            .sum()
+    }
+}
+
+// only the research in progress has used any of its cost up
+fn refund(research: ResearchType, timer: &Timer) -> i32 {
+    let spent = research.cost() as f32 * timer.fraction();
+    research.cost() - spent.round() as i32
+}
@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+
+use super::components::ResearchType;
+
+#[derive(Event)]
+pub struct QueueResearchEv(pub ResearchType);
+
+#[derive(Event)]
+pub struct CancelResearchEv(pub ResearchType);
@@ -0,0 +1,172 @@
+use bevy::prelude::*;
+
+pub mod components;
+pub mod events;
+pub mod resources;
+
+use components::*;
+use events::*;
+use resources::*;
+
+use crate::bank::{AdjustFundsEv, Bank};
+use crate::combat::components::{Health, Weapon};
+use crate::resources::DbgOptions;
+use crate::structures::resources::PowerGrids;
+use crate::teams::Team;
+use crate::units::components::Unit;
+
+pub struct ResearchPlugin;
+
+impl Plugin for ResearchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ResourcesPlugin)
+            .add_systems(Update, research_timer)
+            .add_observer(obs_queue_research)
+            .add_observer(obs_cancel_research)
+            .add_observer(obs_upgrade_new_unit)
+            .add_observer(obs_upgrade_new_weapon);
+    }
+}
+
+fn research_timer(
+    mut q_research_queue: Query<(&Team, &mut ResearchQueue)>,
+    mut q_unit: Query<(&Team, &mut Health, Option<&mut Weapon>), With<Unit>>,
+    mut completed: ResMut<CompletedResearch>,
+    power_grids: Res<PowerGrids>,
+    dbg: Res<DbgOptions>,
+    time: Res<Time>,
+) {
+    for (team, mut research_queue) in q_research_queue.iter_mut() {
+        let Some((research, timer)) = research_queue.0.first_mut() else {
+            continue;
+        };
+
+        // low power slows research down, just like production
+        let delta = time.delta().mul_f32(power_grids.get(team).build_rate());
+        if !timer.tick(delta).just_finished() {
+            continue;
+        }
+
+        let research = *research;
+        research_queue.0.remove(0);
+
+        // research is tracked for the local team only
+        if !team.is_local() {
+            continue;
+        }
+
+        dbg.print(&format!("Research complete: {}", research.name()));
+        completed.0.insert(research);
+
+        // units already on the field get the upgrade right away
+        for (unit_team, mut health, weapon) in q_unit.iter_mut() {
+            if unit_team != team {
+                continue;
+            }
+
+            research.upgrade_health(&mut health);
+            if let Some(mut weapon) = weapon {
+                research.upgrade_weapon(&mut weapon);
+            }
+        }
+    }
+}
+
+// research goes to the local research center with the shortest queue
+fn obs_queue_research(
+    trigger: Trigger<QueueResearchEv>,
+    mut cmds: Commands,
+    mut q_research_queue: Query<(&mut ResearchQueue, &Team)>,
+    completed: Res<CompletedResearch>,
+    bank: Res<Bank>,
+    dbg: Res<DbgOptions>,
+) {
+    let research = trigger.0;
+
+    if !completed.is_available(research) {
+        return;
+    }
+
+    let already_queued = q_research_queue
+        .iter()
+        .any(|(research_queue, team)| team.is_local() && research_queue.contains(research));
+
+    if already_queued {
+        dbg.print("Research already queued");
+        return;
+    }
+
+    if bank.funds < research.cost() {
+        dbg.print("Not enough funds");
+        return;
+    }
+
+    let research_center = q_research_queue
+        .iter_mut()
+        .filter(|(_, team)| team.is_local())
+        .min_by_key(|(research_queue, _)| research_queue.0.len());
+
+    let Some((mut research_queue, _)) = research_center else {
+        return;
+    };
+
+    research_queue.push(research);
+    cmds.trigger(AdjustFundsEv(-research.cost()));
+}
+
+fn obs_cancel_research(
+    trigger: Trigger<CancelResearchEv>,
+    mut cmds: Commands,
+    mut q_research_queue: Query<(&mut ResearchQueue, &Team)>,
+) {
+    let research = trigger.0;
+
+    for (mut research_queue, team) in q_research_queue.iter_mut() {
+        if !team.is_local() {
+            continue;
+        }
+
+        if let Some(refund) = research_queue.cancel(research) {
+            cmds.trigger(AdjustFundsEv(refund));
+            return;
+        }
+    }
+}
+
+// newly built units come off the line with everything researched so far
+fn obs_upgrade_new_unit(
+    trigger: Trigger<OnAdd, Unit>,
+    mut q_unit: Query<(&Team, &mut Health)>,
+    completed: Res<CompletedResearch>,
+) {
+    let Ok((team, mut health)) = q_unit.get_mut(trigger.target()) else {
+        return;
+    };
+
+    if !team.is_local() {
+        return;
+    }
+
+    for research in completed.0.iter() {
+        research.upgrade_health(&mut health);
+    }
+}
+
+// armed units get their weapon just after they're spawned, it's upgraded once it's there
+fn obs_upgrade_new_weapon(
+    trigger: Trigger<OnAdd, Weapon>,
+    mut q_unit: Query<(&Team, &mut Weapon), With<Unit>>,
+    completed: Res<CompletedResearch>,
+) {
+    let Ok((team, mut weapon)) = q_unit.get_mut(trigger.target()) else {
+        return;
+    };
+
+    if !team.is_local() {
+        return;
+    }
+
+    for research in completed.0.iter() {
+        research.upgrade_weapon(&mut weapon);
+    }
+}
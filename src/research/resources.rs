@@ -0,0 +1,30 @@
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+use super::components::ResearchType;
+
+pub struct ResourcesPlugin;
+
+impl Plugin for ResourcesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CompletedResearch>();
+    }
+}
+
+// research finished by the local team
+#[derive(Resource, Default, Debug)]
+pub struct CompletedResearch(pub HashSet<ResearchType>);
+
+impl CompletedResearch {
+    pub fn contains(&self, research: ResearchType) -> bool {
+        self.0.contains(&research)
+    }
+
+    // not finished yet, and everything it depends on is
+    pub fn is_available(&self, research: ResearchType) -> bool {
+        !self.contains(research)
+            && research
+                .prerequisite()
+                .is_none_or(|prerequisite| self.contains(prerequisite))
+    }
+}
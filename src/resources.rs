@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 
+use crate::units::components::UnitOrder;
 use crate::*;
 
 pub struct ResourcesPlugin;
@@ -53,6 +54,9 @@ impl MouseCoords {
 pub struct SelectBox {
     pub viewport: Viewport,
     pub world: World,
+    // selection from before a Shift/Ctrl drag, which the box adds to (or toggles against)
+    pub kept: Vec<Entity>,
+    pub toggle: bool,
 }
 
 impl SelectBox {
@@ -108,6 +112,7 @@ pub enum CursorState {
     Select,
     Build,
     Standard,
+    AttackMove,
 }
 
 impl Default for CursorState {
@@ -121,4 +126,5 @@ pub struct GameCommands {
     pub drag_select: bool,
     pub is_any_unit_selected: bool,
     pub hvr_cmd_interface: bool,
+    pub pending_order: Option<UnitOrder>, // patrol/guard/attack-move, waiting on a click
 }
@@ -0,0 +1,168 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use bevy_rts_pathfinding::components as pf_comps;
+use std::time::Duration;
+
+use crate::{
+    asset_manager::{
+        audio::{AudioCmd, UnitAudioEv},
+        models::MyModels,
+    },
+    cmd_interface::resources::BuildQueueCount,
+    game_state::GameState,
+    teams::Team,
+    units::{
+        components::{Deploying, PathTo, Speed},
+        events::QueueSolderEv,
+    },
+};
+
+use super::resources::PowerGrids;
+use super::{components::*, events::BuildSoldierEv};
+
+pub struct BarracksPlugin;
+
+impl Plugin for BarracksPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (build_soldier_timer, move_soldier_from_barracks).run_if(in_state(GameState::InGame)),
+        )
+        .add_observer(obs_queue_soldier)
+        .add_observer(obs_build_soldier);
+    }
+}
+
+// walk the soldier out of the barracks door
+#[derive(Component)]
+struct NewSoldier {
+    start_pos: Vec3,
+    rally_point: Option<Vec3>,
+    timer: Timer,
+}
+
+impl NewSoldier {
+    fn new(start_pos: Vec3, rally_point: Option<Vec3>) -> Self {
+        Self {
+            start_pos,
+            rally_point,
+            timer: Timer::new(Duration::from_millis(250), TimerMode::Once),
+        }
+    }
+}
+
+fn build_soldier_timer(
+    mut cmds: Commands,
+    mut q_build_queue: Query<(Entity, &StructureType, &Team, &mut BuildQueue)>,
+    power_grids: Res<PowerGrids>,
+    time: Res<Time>,
+) {
+    for (structure_ent, structure_type, team, mut build_queue) in q_build_queue.iter_mut() {
+        if *structure_type != StructureType::Barracks {
+            continue;
+        }
+
+        if let Some((unit_type, timer)) = build_queue.0.first_mut() {
+            // low power slows production down
+            let delta = time.delta().mul_f32(power_grids.get(team).build_rate());
+            if timer.tick(delta).just_finished() {
+                cmds.trigger(BuildSoldierEv {
+                    unit: *unit_type,
+                    structure: structure_ent,
+                });
+                build_queue.0.remove(0);
+            }
+        }
+    }
+}
+
+// new soldiers are queued at the primary barracks (or any barracks, if there is no primary)
+fn obs_queue_soldier(
+    trigger: Trigger<QueueSolderEv>,
+    mut q_build_queue: Query<(&mut BuildQueue, &StructureType, &Team, Has<PrimaryBarracks>)>,
+) {
+    let unit = trigger.unit;
+    let queue_team = trigger.team;
+
+    let barracks = q_build_queue
+        .iter_mut()
+        .filter(|(_, structure_type, team, _)| {
+            **structure_type == StructureType::Barracks && **team == queue_team
+        })
+        .max_by_key(|(_, _, _, is_primary)| *is_primary);
+
+    if let Some((mut build_queue, _, _, _)) = barracks {
+        build_queue.push(unit);
+    }
+}
+
+fn obs_build_soldier(
+    trigger: Trigger<BuildSoldierEv>,
+    mut cmds: Commands,
+    q_structure: Query<(&Transform, &Team, Option<&RallyPoint>)>,
+    my_models: Res<MyModels>,
+    mut build_queue_count: ResMut<BuildQueueCount>,
+) {
+    let unit_type = trigger.unit;
+
+    let Ok((structure_trans, team, rally_point)) = q_structure.get(trigger.structure) else {
+        return;
+    };
+
+    // just inside the door, at the front of the barracks
+    let forward: Vec3 = structure_trans.rotation * Vec3::new(0.0, 0.0, -10.0);
+    let mut spawn_location = structure_trans.translation + forward;
+    spawn_location.y = 2.0; // TODO: Fix this hardcoded value. Needs to be dynamic based on unit models height
+
+    let soldier_transform = Transform {
+        translation: spawn_location,
+        rotation: structure_trans.rotation,
+        ..Default::default()
+    };
+
+    let unit = unit_type.build(soldier_transform, *team, &my_models);
+
+    // the build queue count and "unit ready" voice only concern the local team
+    if team.is_local() {
+        cmds.trigger(UnitAudioEv::new(AudioCmd::Ready, unit_type.clone()));
+        build_queue_count.remove(&unit_type);
+    }
+
+    cmds.spawn((
+        unit,
+        Deploying,
+        NewSoldier::new(
+            soldier_transform.translation,
+            rally_point.map(|rally| rally.0),
+        ),
+    ));
+}
+
+fn move_soldier_from_barracks(
+    mut cmds: Commands,
+    mut q_new_soldier: Query<(Entity, &mut Velocity, &Transform, &Speed, &mut NewSoldier)>,
+    time: Res<Time>,
+) {
+    for (entity, mut vel, tf, speed, mut new_soldier) in q_new_soldier.iter_mut() {
+        new_soldier.timer.tick(time.delta());
+
+        if !new_soldier.timer.finished() {
+            continue;
+        }
+
+        let forward = tf.rotation * Vec3::new(0.0, 0.0, -1.0);
+        vel.linvel = forward.normalize() * speed.0;
+
+        let distance_traveled = tf.translation.distance(new_soldier.start_pos);
+        if distance_traveled >= 25.0 {
+            vel.linvel = Vec3::ZERO;
+            cmds.entity(entity).remove::<(NewSoldier, Deploying)>();
+
+            // heads for the rally point around structures, same as an ordered move
+            if let Some(rally_point) = new_soldier.rally_point {
+                cmds.entity(entity)
+                    .insert((pf_comps::Destination, PathTo(rally_point)));
+            }
+        }
+    }
+}
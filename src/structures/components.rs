@@ -1,11 +1,15 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 use bevy_rts_pathfinding::components::{self as pf_comps};
+use std::time::Duration;
 use strum_macros::EnumIter;
 
 use crate::{
     asset_manager::{imgs::MyImgs, models::MyModels},
-    units::components::BorderSize,
+    combat::components::{Health, Trajectory, Turret, Weapon},
+    research::components::ResearchQueue,
+    teams::Team,
+    units::components::{BorderSize, UnitType},
 };
 
 #[derive(Component)]
@@ -30,6 +34,56 @@ pub struct PrimaryVehicleDepot;
 #[derive(Component)]
 pub struct PrimaryBarracks;
 
+// a placed structure that is still being built. it can't fight, produce or unlock anything yet
+#[derive(Component)]
+pub struct UnderConstruction(pub Timer);
+
+impl UnderConstruction {
+    pub fn new(structure: StructureType) -> Self {
+        let build_time = Duration::from_secs(structure.build_time());
+        Self(Timer::new(build_time, TimerMode::Once))
+    }
+}
+
+// an armed structure that has shut down because its team is low on power
+#[derive(Component)]
+pub struct Unpowered;
+
+// where units leave to once a production structure has built them
+#[derive(Component, Debug, Clone, Copy)]
+pub struct RallyPoint(pub Vec3);
+
+// units waiting to be built by a production structure, only the first one is in production
+#[derive(Component, Default)]
+pub struct BuildQueue(pub Vec<(UnitType, Timer)>);
+
+impl BuildQueue {
+    pub fn push(&mut self, unit: UnitType) {
+        let timer = Timer::new(Duration::from_secs(unit.build_time()), TimerMode::Once);
+        self.0.push((unit, timer));
+    }
+
+    // removes a queued unit and returns the refund
+    pub fn cancel(&mut self, idx: usize) -> i32 {
+        let (unit, timer) = self.0.remove(idx);
+        refund(unit, &timer)
+    }
+
+    // what everything still in the queue is worth, for when the structure is destroyed
+    pub fn refund_all(&self) -> i32 {
+        self.0
+            .iter()
+            .map(|(unit, timer)| refund(*unit, timer))
+            .sum()
+    }
+}
+
+// only the unit in production has progressed, so anything behind it is refunded in full
+fn refund(unit: UnitType, timer: &Timer) -> i32 {
+    let spent = unit.cost() as f32 * timer.fraction();
+    unit.cost() - spent.round() as i32
+}
+
 #[derive(Component, Clone, Copy, EnumIter, PartialEq)]
 pub enum StructureType {
     Cannon,
@@ -37,9 +91,16 @@ pub enum StructureType {
     VehicleDepot,
     ResearchCenter,
     SatelliteDish,
+    Refinery,
+    PowerPlant,
 }
 
 impl StructureType {
+    // structures that build units
+    pub fn is_production(&self) -> bool {
+        matches!(self, StructureType::Barracks | StructureType::VehicleDepot)
+    }
+
     pub fn select_border(&self) -> BorderSize {
         match self {
             StructureType::Cannon => BorderSize(Vec2::new(40.0, 40.0)),
@@ -47,6 +108,95 @@ impl StructureType {
             StructureType::VehicleDepot => BorderSize(Vec2::new(140.0, 100.0)),
             StructureType::ResearchCenter => BorderSize(Vec2::new(100.0, 100.0)),
             StructureType::SatelliteDish => BorderSize(Vec2::new(75.0, 90.0)),
+            StructureType::Refinery => BorderSize(Vec2::new(100.0, 100.0)),
+            StructureType::PowerPlant => BorderSize(Vec2::new(75.0, 90.0)),
+        }
+    }
+
+    pub fn hp(&self) -> i32 {
+        match self {
+            StructureType::Cannon => 300,
+            StructureType::Barracks => 500,
+            StructureType::VehicleDepot => 1000,
+            StructureType::ResearchCenter => 600,
+            StructureType::SatelliteDish => 400,
+            StructureType::Refinery => 800,
+            StructureType::PowerPlant => 500,
+        }
+    }
+
+    pub fn dmg(&self) -> Option<i32> {
+        match self {
+            StructureType::Cannon => Some(25),
+            _ => None,
+        }
+    }
+
+    pub fn range(&self) -> Option<f32> {
+        match self {
+            StructureType::Cannon => Some(150.0),
+            _ => None,
+        }
+    }
+
+    // seconds between shots
+    pub fn rate_of_fire(&self) -> Option<f32> {
+        match self {
+            StructureType::Cannon => Some(1.0),
+            _ => None,
+        }
+    }
+
+    // only defensive structures are armed
+    pub fn weapon(&self) -> Option<Weapon> {
+        let (Some(dmg), Some(range), Some(rate_of_fire)) =
+            (self.dmg(), self.range(), self.rate_of_fire())
+        else {
+            return None;
+        };
+
+        let muzzle_offset = Vec3::new(0.0, 3.0, -6.0);
+        Some(Weapon::new(
+            dmg,
+            range,
+            rate_of_fire,
+            250.0,
+            muzzle_offset,
+            Trajectory::Direct,
+        ))
+    }
+
+    // the node of the model that turns to aim. the cannon model is a single mesh, so all of it turns
+    pub fn turret_node(&self) -> Option<&'static str> {
+        match self {
+            StructureType::Cannon => Some("Cube"),
+            _ => None,
+        }
+    }
+
+    // how far the structure can see through the fog of war
+    pub fn sight(&self) -> f32 {
+        match self {
+            StructureType::Cannon => 170.0,
+            StructureType::Barracks => 100.0,
+            StructureType::VehicleDepot => 100.0,
+            StructureType::ResearchCenter => 80.0,
+            StructureType::SatelliteDish => 200.0,
+            StructureType::Refinery => 80.0,
+            StructureType::PowerPlant => 60.0,
+        }
+    }
+
+    // power produced (positive) or consumed (negative) once the structure is finished
+    pub fn power(&self) -> i32 {
+        match self {
+            StructureType::Cannon => -20,
+            StructureType::Barracks => -10,
+            StructureType::VehicleDepot => -30,
+            StructureType::ResearchCenter => -25,
+            StructureType::SatelliteDish => -20,
+            StructureType::Refinery => -20,
+            StructureType::PowerPlant => 100,
         }
     }
 
@@ -57,6 +207,8 @@ impl StructureType {
             StructureType::VehicleDepot => 15,
             StructureType::ResearchCenter => 20,
             StructureType::SatelliteDish => 25,
+            StructureType::Refinery => 15,
+            StructureType::PowerPlant => 10,
         }
     }
 
@@ -67,6 +219,8 @@ impl StructureType {
             StructureType::VehicleDepot => 2000,
             StructureType::ResearchCenter => 1500,
             StructureType::SatelliteDish => 1000,
+            StructureType::Refinery => 1000,
+            StructureType::PowerPlant => 600,
         }
     }
 
@@ -77,6 +231,8 @@ impl StructureType {
             StructureType::VehicleDepot => "Vehicle Depot".to_string(),
             StructureType::ResearchCenter => "Research Center".to_string(),
             StructureType::SatelliteDish => "Satellite Dish".to_string(),
+            StructureType::Refinery => "Refinery".to_string(),
+            StructureType::PowerPlant => "Power Plant".to_string(),
         }
     }
 
@@ -87,6 +243,8 @@ impl StructureType {
             StructureType::VehicleDepot => my_imgs.structure_vehicle_depot.clone(),
             StructureType::ResearchCenter => my_imgs.structure_research_center.clone(),
             StructureType::SatelliteDish => my_imgs.structure_satellite_dish.clone(),
+            StructureType::Refinery => my_imgs.structure_refinery.clone(),
+            StructureType::PowerPlant => my_imgs.structure_power_plant.clone(),
         }
     }
 
@@ -97,12 +255,15 @@ impl StructureType {
             StructureType::VehicleDepot => my_models.vehicle_depot.clone(),
             StructureType::ResearchCenter => my_models.research_center.clone(),
             StructureType::SatelliteDish => my_models.satellite_dish.clone(),
+            StructureType::Refinery => my_models.refinery.clone(),
+            StructureType::PowerPlant => my_models.power_plant.clone(),
         }
     }
 
     pub fn place(
         &self,
         placeholder_ent: Entity,
+        team: Team,
         my_models: &MyModels,
         scene: &mut SceneRoot,
         rb: &mut RigidBody,
@@ -117,10 +278,33 @@ impl StructureType {
             .insert((
                 pf_comps::RtsObj,
                 Structure,
+                team,
                 self.select_border(),
-                NewlyPlacedStructure,
+                Health::new(self.hp()),
+                UnderConstruction::new(*self),
                 Name::new(self.to_string()),
             ));
+
+        // only structures placed by the local player come from a click that could select them
+        if team.is_local() {
+            cmds.entity(placeholder_ent).insert(NewlyPlacedStructure);
+        }
+    }
+
+    // gives a structure that has finished construction its functionality
+    pub fn complete(&self, structure_ent: Entity, cmds: &mut Commands) {
+        if let Some(weapon) = self.weapon() {
+            cmds.entity(structure_ent)
+                .insert((weapon, Turret::default()));
+        }
+
+        if self.is_production() {
+            cmds.entity(structure_ent).insert(BuildQueue::default());
+        }
+
+        if *self == StructureType::ResearchCenter {
+            cmds.entity(structure_ent).insert(ResearchQueue::default());
+        }
     }
 
     pub fn invalid_placement(&self, my_models: &MyModels, scene: &mut SceneRoot) {
@@ -136,6 +320,10 @@ impl StructureType {
             StructureType::SatelliteDish => {
                 scene.0 = my_models.placeholders.satellite_dish_invalid.clone()
             }
+            StructureType::Refinery => scene.0 = my_models.placeholders.refinery_invalid.clone(),
+            StructureType::PowerPlant => {
+                scene.0 = my_models.placeholders.power_plant_invalid.clone()
+            }
         }
     }
 
@@ -152,12 +340,14 @@ impl StructureType {
             StructureType::SatelliteDish => {
                 scene.0 = my_models.placeholders.satellite_dish_valid.clone()
             }
+            StructureType::Refinery => scene.0 = my_models.placeholders.refinery_valid.clone(),
+            StructureType::PowerPlant => scene.0 = my_models.placeholders.power_plant_valid.clone(),
         }
     }
 
     pub fn build_placeholder(
         &self,
-        my_models: Res<MyModels>,
+        my_models: &MyModels,
     ) -> (
         SceneRoot,
         Collider,
@@ -190,6 +380,14 @@ impl StructureType {
                 size = Vec3::new(32.0, 8.0, 32.0);
                 structure = SceneRoot(my_models.placeholders.satellite_dish_valid.clone());
             }
+            StructureType::Refinery => {
+                size = Vec3::new(30.0, 18.0, 30.0);
+                structure = SceneRoot(my_models.placeholders.refinery_valid.clone());
+            }
+            StructureType::PowerPlant => {
+                size = Vec3::new(32.0, 8.0, 32.0);
+                structure = SceneRoot(my_models.placeholders.power_plant_valid.clone());
+            }
         }
 
         (
@@ -218,3 +416,38 @@ impl StructurePlaceholder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queued_units_are_refunded_in_full() {
+        let mut build_queue = BuildQueue::default();
+        build_queue.push(UnitType::TankGen1);
+        build_queue.push(UnitType::Rifleman);
+
+        assert_eq!(
+            build_queue.refund_all(),
+            UnitType::TankGen1.cost() + UnitType::Rifleman.cost()
+        );
+        assert_eq!(build_queue.cancel(1), UnitType::Rifleman.cost());
+        assert_eq!(build_queue.0.len(), 1);
+    }
+
+    #[test]
+    fn the_unit_in_production_is_refunded_what_is_left() {
+        let mut build_queue = BuildQueue::default();
+        build_queue.push(UnitType::TankGen1);
+        build_queue.push(UnitType::TankGen1);
+
+        // halfway through building the first tank
+        let half = Duration::from_secs(UnitType::TankGen1.build_time()) / 2;
+        build_queue.0[0].1.tick(half);
+
+        let cost = UnitType::TankGen1.cost();
+        assert_eq!(build_queue.refund_all(), cost / 2 + cost);
+        assert_eq!(build_queue.cancel(0), cost / 2);
+        assert_eq!(build_queue.refund_all(), cost);
+    }
+}
@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_rts_camera::RtsCamera;
+use bevy_rts_pathfinding::components::{self as pf_comps};
+
+use super::components::*;
+use crate::bank::AdjustFundsEv;
+use crate::cmd_interface::events::CancelStructureEv;
+use crate::game_state::GameState;
+use crate::teams::Team;
+use crate::utils::billboard_sync;
+
+pub struct ConstructionPlugin;
+
+impl Plugin for ConstructionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                construct_structures,
+                sync_construction_txt.run_if(any_with_component::<ConstructionTxt>),
+            )
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_observer(spawn_construction_txt)
+        .add_observer(obs_cancel_structure);
+    }
+}
+
+// the progress label floating over a structure that is being built
+#[derive(Component)]
+struct ConstructionTxt(Entity);
+
+fn spawn_construction_txt(trigger: Trigger<OnAdd, UnderConstruction>, mut cmds: Commands) {
+    let txt = (
+        ConstructionTxt(trigger.target()),
+        Text::new("0%"),
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node::default(),
+        ZIndex(10),
+        Name::new("Construction Progress Txt"),
+    );
+
+    cmds.spawn(txt);
+}
+
+fn construct_structures(
+    mut cmds: Commands,
+    mut q_construction: Query<(Entity, &StructureType, &mut UnderConstruction)>,
+    time: Res<Time>,
+) {
+    for (structure_ent, structure_type, mut construction) in q_construction.iter_mut() {
+        if construction.0.tick(time.delta()).just_finished() {
+            cmds.entity(structure_ent).remove::<UnderConstruction>();
+            structure_type.complete(structure_ent, &mut cmds);
+        }
+    }
+}
+
+fn sync_construction_txt(
+    mut cmds: Commands,
+    mut q_txt: Query<(Entity, &ConstructionTxt, &mut Text, &mut Node)>,
+    q_construction: Query<(&Transform, &pf_comps::RtsObjSize, &UnderConstruction)>,
+    cam_q: Query<(&Camera, &GlobalTransform), With<RtsCamera>>,
+    window_q: Query<&Window, With<PrimaryWindow>>,
+) {
+    let Ok((cam, cam_trans)) = cam_q.single() else {
+        return;
+    };
+
+    let Ok(window) = window_q.single() else {
+        return;
+    };
+
+    for (txt_ent, txt, mut text, mut style) in q_txt.iter_mut() {
+        // construction finished, was cancelled, or the structure was destroyed
+        let Ok((trans, obj_size, construction)) = q_construction.get(txt.0) else {
+            cmds.entity(txt_ent).despawn();
+            continue;
+        };
+
+        let progress = (construction.0.fraction() * 100.0) as u32;
+        text.0 = format!("{}%", progress);
+
+        let size = obj_size.0.xy();
+        billboard_sync(cam, cam_trans, window, trans, size, &mut style, 20.0);
+    }
+}
+
+// cancels the most recently started construction of that type, refunding what hasn't been built
+fn obs_cancel_structure(
+    trigger: Trigger<CancelStructureEv>,
+    mut cmds: Commands,
+    q_construction: Query<(Entity, &StructureType, &Team, &UnderConstruction)>,
+) {
+    let structure_type = trigger.0;
+
+    let latest = q_construction
+        .iter()
+        .filter(|(_, other, team, _)| **other == structure_type && team.is_local())
+        .min_by_key(|(_, _, _, construction)| construction.0.elapsed());
+
+    let Some((structure_ent, _, _, construction)) = latest else {
+        return;
+    };
+
+    let spent = structure_type.cost() as f32 * construction.0.fraction();
+    let refund = structure_type.cost() - spent.round() as i32;
+
+    cmds.entity(structure_ent).despawn();
+    cmds.trigger(AdjustFundsEv(refund));
+}
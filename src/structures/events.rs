@@ -8,5 +8,15 @@ pub struct SetPrimaryStructureEv(pub Entity);
 #[derive(Event)]
 pub struct DeselectAllStructuresEv;
 
+// a unit finished production in the given structure
 #[derive(Event)]
-pub struct BuildVehicleEv(pub UnitType);
+pub struct BuildVehicleEv {
+    pub unit: UnitType,
+    pub structure: Entity,
+}
+
+#[derive(Event)]
+pub struct BuildSoldierEv {
+    pub unit: UnitType,
+    pub structure: Entity,
+}
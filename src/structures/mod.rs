@@ -1,5 +1,6 @@
 use std::f32::consts::PI;
 
+use barracks::BarracksPlugin;
 use bevy::input::mouse::MouseWheel;
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
@@ -9,21 +10,33 @@ use bevy_mod_outline::OutlineVolume;
 use bevy_rapier3d::prelude::*;
 use bevy_rts_camera::RtsCamera;
 use bevy_rts_pathfinding::components::{self as pf_comps};
+use construction::ConstructionPlugin;
 use events::DeselectAllStructuresEv;
 use events::SetPrimaryStructureEv;
+use power::PowerPlugin;
+use rally_point::RallyPointPlugin;
 use resources::StructuresBuilt;
 use vehicle_depot::VehicleDepotPlugin;
 
 use crate::asset_manager::audio::*;
 use crate::asset_manager::models::MyModels;
 use crate::bank::*;
+use crate::cmd_interface::events::CancelUnitEv;
+use crate::cmd_interface::resources::BuildQueueCount;
 use crate::events::*;
+use crate::game_state::GameState;
+use crate::research::components::ResearchQueue;
 use crate::resources::*;
+use crate::teams::Team;
 use crate::utils;
 use crate::utils::billboard_sync;
 
+mod barracks;
 pub mod components;
+mod construction;
 pub mod events;
+mod power;
+mod rally_point;
 pub mod resources;
 mod vehicle_depot;
 
@@ -34,38 +47,161 @@ pub struct StructuresPlugin;
 
 impl Plugin for StructuresPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((VehicleDepotPlugin, ResourcesPlugin))
-            .add_systems(
-                Update,
-                (
-                    mark_primary_structure_initial,
-                    count_structures.after(place_structure),
-                    sync_placeholder,
-                    sync_primary_structure_txt,
-                    deselect_rmb,
-                    validate_structure_placement,
-                    place_structure.after(validate_structure_placement),
-                ),
+        app.add_plugins((
+            VehicleDepotPlugin,
+            BarracksPlugin,
+            ConstructionPlugin,
+            PowerPlugin,
+            RallyPointPlugin,
+            ResourcesPlugin,
+        ))
+        .add_systems(
+            Update,
+            (
+                mark_primary_structure_initial,
+                count_structures.after(place_structure),
+                sync_placeholder,
+                sync_primary_structure_txt,
+                deselect_rmb,
+                validate_structure_placement,
+                place_structure.after(validate_structure_placement),
             )
-            .add_observer(obs_select_structure)
-            .add_observer(obs_deselect)
-            .add_observer(obs_set_primary_structure);
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_observer(obs_structure_removed)
+        .add_observer(obs_cancel_unit)
+        .add_observer(obs_select_structure)
+        .add_observer(obs_deselect)
+        .add_observer(obs_set_primary_structure);
     }
 }
 
-// modifies the 'StructuresBuilt' resource, whenever a structure is placed or removed (destroyed)
+// modifies the 'StructuresBuilt' resource, whenever a structure is finished or removed (destroyed)
 pub fn count_structures(
     mut structures_built: ResMut<StructuresBuilt>,
-    q_structure_added: Query<&StructureType, Added<Structure>>,
+    mut constructed: RemovedComponents<UnderConstruction>,
+    q_structure: Query<(&StructureType, &Team), With<Structure>>,
 ) {
-    for structure in q_structure_added.iter() {
+    for structure_ent in constructed.read() {
+        // cancelled constructions are despawned, so they never make it here
+        let Ok((structure, team)) = q_structure.get(structure_ent) else {
+            continue;
+        };
+
+        if !team.is_local() {
+            continue;
+        }
+
         match structure {
             StructureType::Cannon => structures_built.cannon += 1,
             StructureType::Barracks => structures_built.barracks += 1,
             StructureType::VehicleDepot => structures_built.vehicle_depot += 1,
             StructureType::ResearchCenter => structures_built.research_center += 1,
             StructureType::SatelliteDish => structures_built.satellite_dish += 1,
+            StructureType::Refinery => structures_built.refinery += 1,
+            StructureType::PowerPlant => structures_built.power_plant += 1,
+        }
+    }
+}
+
+// cancels the most recently queued unit of that type across the local team's structures
+fn obs_cancel_unit(
+    trigger: Trigger<CancelUnitEv>,
+    mut cmds: Commands,
+    mut q_build_queue: Query<(&mut BuildQueue, &Team)>,
+    mut build_queue_count: ResMut<BuildQueueCount>,
+) {
+    let unit_type = trigger.0;
+
+    let latest = q_build_queue
+        .iter_mut()
+        .filter(|(_, team)| team.is_local())
+        .filter_map(|(build_queue, _)| {
+            let idx = build_queue
+                .0
+                .iter()
+                .rposition(|(unit, _)| *unit == unit_type)?;
+            Some((build_queue, idx))
+        })
+        .max_by_key(|(_, idx)| *idx);
+
+    let Some((mut build_queue, idx)) = latest else {
+        return;
+    };
+
+    let refund = build_queue.cancel(idx);
+    build_queue_count.remove(&unit_type);
+    cmds.trigger(AdjustFundsEv(refund));
+}
+
+// OnRemove runs before the components are dropped, so the structure type is still readable here
+fn obs_structure_removed(
+    trigger: Trigger<OnRemove, Structure>,
+    mut cmds: Commands,
+    mut structures_built: ResMut<StructuresBuilt>,
+    mut build_queue_count: ResMut<BuildQueueCount>,
+    q_structure: Query<(
+        &StructureType,
+        &Team,
+        Has<PrimaryStructure>,
+        Has<UnderConstruction>,
+        Option<&BuildQueue>,
+        Option<&ResearchQueue>,
+    )>,
+    q_others: Query<(Entity, &StructureType, &Team), (With<Structure>, Without<UnderConstruction>)>,
+) {
+    let removed_ent = trigger.target();
+
+    let Ok((structure, team, is_primary, is_under_construction, build_queue, research_queue)) =
+        q_structure.get(removed_ent)
+    else {
+        return;
+    };
+
+    // units that were paid for but never came out are refunded, to the ai's funds too
+    if let Some(build_queue) = build_queue {
+        if team.is_local() {
+            for (unit_type, _) in build_queue.0.iter() {
+                build_queue_count.remove(unit_type);
+            }
         }
+
+        cmds.trigger(DepositFundsEv {
+            team: *team,
+            amount: build_queue.refund_all(),
+        });
+    }
+
+    // and so is research that never finished
+    if let Some(research_queue) = research_queue.filter(|_| team.is_local()) {
+        cmds.trigger(AdjustFundsEv(research_queue.refund_all()));
+    }
+
+    // structures are only counted once they are finished
+    if team.is_local() && !is_under_construction {
+        let count = match structure {
+            StructureType::Cannon => &mut structures_built.cannon,
+            StructureType::Barracks => &mut structures_built.barracks,
+            StructureType::VehicleDepot => &mut structures_built.vehicle_depot,
+            StructureType::ResearchCenter => &mut structures_built.research_center,
+            StructureType::SatelliteDish => &mut structures_built.satellite_dish,
+            StructureType::Refinery => &mut structures_built.refinery,
+            StructureType::PowerPlant => &mut structures_built.power_plant,
+        };
+        *count = count.saturating_sub(1);
+    }
+
+    if !is_primary {
+        return;
+    }
+
+    // hand the primary role over to another structure of the same type and team
+    let replacement = q_others.iter().find(|(ent, other, other_team)| {
+        *ent != removed_ent && *other == structure && *other_team == team
+    });
+
+    if let Some((replacement_ent, _, _)) = replacement {
+        cmds.trigger(SetPrimaryStructureEv(replacement_ent));
     }
 }
 
@@ -76,6 +212,7 @@ fn obs_select_structure(
     game_cmds: Res<GameCommands>,
     mut q: Query<Entity, With<NewlyPlacedStructure>>,
     q_selected: Query<(), With<SelectedStructure>>,
+    q_team: Query<&Team>,
 ) {
     // Hack. This is used to prevent a newly placed structure from automatically being selected
     if let Ok(ent) = q.single_mut() {
@@ -89,6 +226,11 @@ fn obs_select_structure(
 
     let structure_ent = trigger.0;
 
+    // enemy structures can't be selected
+    if !q_team.get(structure_ent).is_ok_and(|team| team.is_local()) {
+        return;
+    }
+
     // Check if the structure is already selected.
     if q_selected.get(structure_ent).is_ok() {
         dbg.print("Structure is already selected");
@@ -145,7 +287,14 @@ fn place_structure(
 
     if input.just_pressed(MouseButton::Left) && placeholder.is_valid {
         *cursor_state = CursorState::Standard;
-        structure.place(placeholder_ent, &my_models, &mut scene, &mut rb, &mut cmds);
+        structure.place(
+            placeholder_ent,
+            Team::Player,
+            &my_models,
+            &mut scene,
+            &mut rb,
+            &mut cmds,
+        );
 
         // Adjust bank
         cmds.trigger(AdjustFundsEv(-structure.cost()));
@@ -243,7 +392,16 @@ fn sync_placeholder(
     }
 }
 
-fn deselect_rmb(mut cmds: Commands, input: Res<ButtonInput<MouseButton>>) {
+fn deselect_rmb(
+    mut cmds: Commands,
+    input: Res<ButtonInput<MouseButton>>,
+    q_selected: Query<&StructureType, With<SelectedStructure>>,
+) {
+    // right clicking with a production structure selected sets its rally point instead
+    if q_selected.iter().any(|structure| structure.is_production()) {
+        return;
+    }
+
     if input.just_released(MouseButton::Right) {
         cmds.trigger(DeselectAllStructuresEv);
     }
@@ -313,23 +471,26 @@ fn sync_primary_structure_txt(
 
 fn obs_set_primary_structure(
     trigger: Trigger<SetPrimaryStructureEv>,
-    q_primary_barracks: Query<Entity, With<PrimaryBarracks>>,
-    q_primary_vehicle_depot: Query<Entity, With<PrimaryVehicleDepot>>,
-    q_structure_type: Query<&StructureType>,
+    q_primary_barracks: Query<(Entity, &Team), With<PrimaryBarracks>>,
+    q_primary_vehicle_depot: Query<(Entity, &Team), With<PrimaryVehicleDepot>>,
+    q_structure_type: Query<(&StructureType, &Team)>,
     mut cmds: Commands,
     dbg: Res<DbgOptions>,
 ) {
     dbg.print("Assigning new primary structure");
     let new_primary = trigger.0;
 
-    let Ok(structure_type) = q_structure_type.get(new_primary) else {
+    let Ok((structure_type, team)) = q_structure_type.get(new_primary) else {
         return;
     };
 
+    // every team keeps its own primary structures
+    let same_team = |(ent, other_team): (Entity, &Team)| (other_team == team).then_some(ent);
+
     match structure_type {
         StructureType::Barracks => {
             // remove any other primary barracks
-            for ent in q_primary_barracks.iter() {
+            for ent in q_primary_barracks.iter().filter_map(same_team) {
                 cmds.entity(ent).remove::<PrimaryStructure>();
                 cmds.entity(ent).remove::<PrimaryBarracks>();
             }
@@ -338,7 +499,7 @@ fn obs_set_primary_structure(
         }
         StructureType::VehicleDepot => {
             // remove any other primary vehicle depots
-            for ent in q_primary_vehicle_depot.iter() {
+            for ent in q_primary_vehicle_depot.iter().filter_map(same_team) {
                 cmds.entity(ent).remove::<PrimaryStructure>();
                 cmds.entity(ent).remove::<PrimaryVehicleDepot>();
             }
@@ -351,20 +512,25 @@ fn obs_set_primary_structure(
     cmds.entity(new_primary).insert(PrimaryStructure);
 }
 
+// the first barracks/vehicle depot of a team becomes its primary one
 fn mark_primary_structure_initial(
     mut cmds: Commands,
-    structures_built: ResMut<StructuresBuilt>,
-    q_structures: Query<(Entity, &StructureType), Added<Structure>>,
+    mut constructed: RemovedComponents<UnderConstruction>,
+    q_structures: Query<(&StructureType, &Team), With<Structure>>,
+    q_primary: Query<(&StructureType, &Team), With<PrimaryStructure>>,
 ) {
-    for (structure_ent, structure_type) in q_structures.iter() {
+    for structure_ent in constructed.read() {
+        let Ok((structure_type, team)) = q_structures.get(structure_ent) else {
+            continue;
+        };
+
         match structure_type {
-            StructureType::Barracks => {
-                if structures_built.barracks == 1 {
-                    cmds.trigger(SetPrimaryStructureEv(structure_ent));
-                }
-            }
-            StructureType::VehicleDepot => {
-                if structures_built.vehicle_depot == 1 {
+            StructureType::Barracks | StructureType::VehicleDepot => {
+                let has_primary = q_primary.iter().any(|(primary_type, primary_team)| {
+                    primary_type == structure_type && primary_team == team
+                });
+
+                if !has_primary {
                     cmds.trigger(SetPrimaryStructureEv(structure_ent));
                 }
             }
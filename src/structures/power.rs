@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::components::*;
+use super::resources::{PowerGrid, PowerGrids};
+use crate::cmd_interface::components::PowerTxt;
+use crate::game_state::GameState;
+use crate::teams::Team;
+
+const CLR_POWER_OK: Color = Color::WHITE;
+const CLR_POWER_LOW: Color = Color::srgb(0.95, 0.25, 0.2);
+
+pub struct PowerPlugin;
+
+impl Plugin for PowerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                update_power_grids,
+                (toggle_unpowered, update_power_txt).run_if(resource_changed::<PowerGrids>),
+            )
+                .chain()
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+// structures only draw or supply power once they are finished
+fn update_power_grids(
+    mut power_grids: ResMut<PowerGrids>,
+    q_structure: Query<(&StructureType, &Team), (With<Structure>, Without<UnderConstruction>)>,
+) {
+    let mut grids = HashMap::<Team, PowerGrid>::new();
+
+    for (structure, team) in q_structure.iter() {
+        let grid = grids.entry(*team).or_default();
+        let power = structure.power();
+
+        if power > 0 {
+            grid.produced += power;
+        } else {
+            grid.consumed -= power;
+        }
+    }
+
+    // only touch the resource when something changed, so change detection stays meaningful
+    if power_grids.0 != grids {
+        power_grids.0 = grids;
+    }
+}
+
+// armed structures shut down while their team is low on power
+fn toggle_unpowered(
+    mut cmds: Commands,
+    power_grids: Res<PowerGrids>,
+    q_structure: Query<
+        (Entity, &StructureType, &Team, Has<Unpowered>),
+        (With<Structure>, Without<UnderConstruction>),
+    >,
+) {
+    for (structure_ent, structure, team, is_unpowered) in q_structure.iter() {
+        if structure.weapon().is_none() {
+            continue;
+        }
+
+        let is_low = power_grids.get(team).is_low();
+
+        if is_low && !is_unpowered {
+            cmds.entity(structure_ent).insert(Unpowered);
+        } else if !is_low && is_unpowered {
+            cmds.entity(structure_ent).remove::<Unpowered>();
+        }
+    }
+}
+
+fn update_power_txt(
+    power_grids: Res<PowerGrids>,
+    mut q_power_txt: Query<(&mut Text, &mut TextColor), With<PowerTxt>>,
+) {
+    let Ok((mut text, mut color)) = q_power_txt.single_mut() else {
+        return;
+    };
+
+    let grid = power_grids.get(&Team::local());
+    text.0 = format!("PWR {}/{}", grid.consumed, grid.produced);
+    color.0 = if grid.is_low() {
+        CLR_POWER_LOW
+    } else {
+        CLR_POWER_OK
+    };
+}
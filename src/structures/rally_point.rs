@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+use bevy_rapier3d::plugin::ReadRapierContext;
+
+use super::components::*;
+use crate::game_state::GameState;
+use crate::resources::{GameCommands, MouseCoords};
+use crate::teams::Team;
+use crate::utils;
+
+pub struct RallyPointPlugin;
+
+impl Plugin for RallyPointPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (set_rally_point, sync_rally_flags, draw_rally_lines)
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+// the flag marking a structure's rally point
+#[derive(Component)]
+struct RallyFlag(Entity);
+
+// right clicking the ground while a production structure is selected moves its rally point
+fn set_rally_point(
+    mut cmds: Commands,
+    input: Res<ButtonInput<MouseButton>>,
+    game_cmds: Res<GameCommands>,
+    mouse_coords: Res<MouseCoords>,
+    read_rapier: ReadRapierContext,
+    q_cam: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    q_selected: Query<(Entity, &StructureType, &Team), With<SelectedStructure>>,
+    mut q_flag: Query<(&RallyFlag, &mut Transform)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if !input.just_released(MouseButton::Right) || game_cmds.hvr_cmd_interface {
+        return;
+    }
+
+    if !mouse_coords.in_bounds() {
+        return;
+    }
+
+    let Ok((structure_ent, structure_type, team)) = q_selected.single() else {
+        return;
+    };
+
+    if !structure_type.is_production() {
+        return;
+    }
+
+    let Ok(rapier_ctx) = read_rapier.single() else {
+        return;
+    };
+
+    let Ok((cam, cam_trans)) = q_cam.single() else {
+        return;
+    };
+
+    // the ground is a sensor, so any hit means the cursor is over a unit or structure
+    if utils::cast_ray(&rapier_ctx, cam, cam_trans, mouse_coords.viewport).is_some() {
+        return;
+    }
+
+    let rally_pos = mouse_coords.world;
+    cmds.entity(structure_ent).insert(RallyPoint(rally_pos));
+
+    if let Some((_, mut flag_trans)) = q_flag.iter_mut().find(|(flag, _)| flag.0 == structure_ent) {
+        flag_trans.translation = rally_pos;
+        return;
+    }
+
+    let flag = (
+        RallyFlag(structure_ent),
+        Transform::from_translation(rally_pos),
+        Visibility::Visible,
+        Name::new("Rally Flag"),
+    );
+
+    let pole = (
+        Mesh3d(meshes.add(Cylinder::new(0.3, 10.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.8, 0.8))),
+        Transform::from_xyz(0.0, 5.0, 0.0),
+        Name::new("Rally Flag Pole"),
+    );
+
+    let cloth = (
+        Mesh3d(meshes.add(Cuboid::new(4.0, 2.5, 0.2))),
+        MeshMaterial3d(materials.add(team.color())),
+        Transform::from_xyz(2.0, 8.75, 0.0),
+        Name::new("Rally Flag Cloth"),
+    );
+
+    cmds.spawn(flag).with_children(|p| {
+        p.spawn(pole);
+        p.spawn(cloth);
+    });
+}
+
+// flags are only shown while their structure is selected, and go away with it
+fn sync_rally_flags(
+    mut cmds: Commands,
+    mut q_flag: Query<(Entity, &RallyFlag, &mut Visibility)>,
+    q_structure: Query<Has<SelectedStructure>, With<Structure>>,
+) {
+    for (flag_ent, flag, mut vis) in q_flag.iter_mut() {
+        let Ok(is_selected) = q_structure.get(flag.0) else {
+            cmds.entity(flag_ent).despawn();
+            continue;
+        };
+
+        *vis = if is_selected {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn draw_rally_lines(
+    mut gizmos: Gizmos,
+    q_selected: Query<(&Transform, &RallyPoint, &Team), With<SelectedStructure>>,
+) {
+    for (trans, rally_point, team) in q_selected.iter() {
+        let mut start = trans.translation;
+        start.y = 0.5;
+        let mut end = rally_point.0;
+        end.y = 0.5;
+
+        gizmos.line(start, end, team.color());
+    }
+}
@@ -1,13 +1,14 @@
 use bevy::prelude::*;
+use std::collections::HashMap;
 
-use crate::units::components::UnitType;
+use crate::teams::Team;
 
 pub struct ResourcesPlugin;
 
 impl Plugin for ResourcesPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<StructuresBuilt>()
-            .init_resource::<VehicleBuildQueue>();
+            .init_resource::<PowerGrids>();
     }
 }
 
@@ -18,7 +19,37 @@ pub struct StructuresBuilt {
     pub vehicle_depot: u32,
     pub research_center: u32,
     pub satellite_dish: u32,
+    pub refinery: u32,
+    pub power_plant: u32,
 }
 
-#[derive(Resource, Default)]
-pub struct VehicleBuildQueue(pub Vec<(UnitType, Timer)>);
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct PowerGrid {
+    pub produced: i32,
+    pub consumed: i32,
+}
+
+impl PowerGrid {
+    pub fn is_low(&self) -> bool {
+        self.consumed > self.produced
+    }
+
+    // production runs at half speed while power is low
+    pub fn build_rate(&self) -> f32 {
+        if self.is_low() {
+            0.5
+        } else {
+            1.0
+        }
+    }
+}
+
+// every team runs its own power grid
+#[derive(Resource, Default, Debug)]
+pub struct PowerGrids(pub HashMap<Team, PowerGrid>);
+
+impl PowerGrids {
+    pub fn get(&self, team: &Team) -> PowerGrid {
+        self.0.get(team).copied().unwrap_or_default()
+    }
+}
@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::Velocity;
+use bevy_rts_pathfinding::components as pf_comps;
 use std::time::Duration;
 
 use crate::{
@@ -8,18 +9,27 @@ use crate::{
         models::MyModels,
     },
     cmd_interface::resources::BuildQueueCount,
-    units::{components::Speed, events::QueueVehicleEv},
+    game_state::GameState,
+    teams::Team,
+    units::{
+        components::{Deploying, PathTo, Speed},
+        events::QueueVehicleEv,
+    },
 };
 
-use super::{components::*, events::BuildVehicleEv, resources::VehicleBuildQueue};
+use super::resources::PowerGrids;
+use super::{components::*, events::BuildVehicleEv};
 
 pub struct VehicleDepotPlugin;
 
 impl Plugin for VehicleDepotPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (build_vehicle_timer, move_vehicle_from_garage))
-            .add_observer(obs_queue_vehicle)
-            .add_observer(obs_build_vehicle);
+        app.add_systems(
+            Update,
+            (build_vehicle_timer, move_vehicle_from_garage).run_if(in_state(GameState::InGame)),
+        )
+        .add_observer(obs_queue_vehicle)
+        .add_observer(obs_build_vehicle);
     }
 }
 
@@ -27,52 +37,86 @@ impl Plugin for VehicleDepotPlugin {
 #[derive(Component)]
 struct NewUnit {
     start_pos: Vec3,
+    rally_point: Option<Vec3>,
     timer: Timer,
 }
 
 impl NewUnit {
-    fn new(start_pos: Vec3) -> Self {
+    fn new(start_pos: Vec3, rally_point: Option<Vec3>) -> Self {
         Self {
             start_pos,
+            rally_point,
             timer: Timer::new(Duration::from_millis(500), TimerMode::Once),
         }
     }
 }
 
+// every depot works through its own queue, so multiple depots build in parallel
 fn build_vehicle_timer(
     mut cmds: Commands,
-    mut build_queue: ResMut<VehicleBuildQueue>,
+    mut q_build_queue: Query<(Entity, &StructureType, &Team, &mut BuildQueue)>,
+    power_grids: Res<PowerGrids>,
     time: Res<Time>,
 ) {
-    if let Some((unit_type, timer)) = build_queue.0.first_mut() {
-        if timer.tick(time.delta()).just_finished() {
-            cmds.trigger(BuildVehicleEv(*unit_type));
-            build_queue.0.remove(0);
+    for (structure_ent, structure_type, team, mut build_queue) in q_build_queue.iter_mut() {
+        if *structure_type != StructureType::VehicleDepot {
+            continue;
+        }
+
+        if let Some((unit_type, timer)) = build_queue.0.first_mut() {
+            // low power slows production down
+            let delta = time.delta().mul_f32(power_grids.get(team).build_rate());
+            if timer.tick(delta).just_finished() {
+                cmds.trigger(BuildVehicleEv {
+                    unit: *unit_type,
+                    structure: structure_ent,
+                });
+                build_queue.0.remove(0);
+            }
         }
     }
 }
 
-fn obs_queue_vehicle(trigger: Trigger<QueueVehicleEv>, mut build_queue: ResMut<VehicleBuildQueue>) {
-    let unit = trigger.0;
-    let timer = Timer::new(Duration::from_secs(unit.build_time()), TimerMode::Once);
-    build_queue.0.push((unit, timer));
+// new vehicles are queued at the primary vehicle depot (or any depot, if there is no primary)
+fn obs_queue_vehicle(
+    trigger: Trigger<QueueVehicleEv>,
+    mut q_build_queue: Query<(
+        &mut BuildQueue,
+        &StructureType,
+        &Team,
+        Has<PrimaryVehicleDepot>,
+    )>,
+) {
+    let unit = trigger.unit;
+    let queue_team = trigger.team;
+
+    let depot = q_build_queue
+        .iter_mut()
+        .filter(|(_, structure_type, team, _)| {
+            **structure_type == StructureType::VehicleDepot && **team == queue_team
+        })
+        .max_by_key(|(_, _, _, is_primary)| *is_primary);
+
+    if let Some((mut build_queue, _, _, _)) = depot {
+        build_queue.push(unit);
+    }
 }
 
 fn obs_build_vehicle(
     trigger: Trigger<BuildVehicleEv>,
     mut cmds: Commands,
-    q_structure: Query<&Transform, With<PrimaryVehicleDepot>>,
+    q_structure: Query<(&Transform, &Team, Option<&RallyPoint>)>,
     my_models: Res<MyModels>,
     // audio: Res<bevy_kira_audio::Audio>,
     // my_audio: Res<MyAudio>,
     mut build_queue_count: ResMut<BuildQueueCount>,
 ) {
-    let Ok(structure_trans) = q_structure.single() else {
+    let unit_type = trigger.unit;
+
+    let Ok((structure_trans, team, rally_point)) = q_structure.get(trigger.structure) else {
         return;
     };
 
-    let unit_type = trigger.0;
-
     let forward: Vec3 = structure_trans.rotation * Vec3::new(-10.0, 0.0, -5.0);
     let mut spawn_location = structure_trans.translation + forward;
     spawn_location.y = 2.0; // TODO: Fix this hardcoded value. Needs to be dynamic based on unit models height
@@ -84,12 +128,22 @@ fn obs_build_vehicle(
         ..Default::default()
     };
 
-    let unit = unit_type.build(vehicle_transform, &my_models);
+    let unit = unit_type.build(vehicle_transform, *team, &my_models);
 
-    cmds.trigger(UnitAudioEv::new(AudioCmd::Ready, unit_type.clone()));
-    cmds.spawn((unit, NewUnit::new(vehicle_transform.translation)));
+    // the build queue count and "unit ready" voice only concern the local team
+    if team.is_local() {
+        cmds.trigger(UnitAudioEv::new(AudioCmd::Ready, unit_type.clone()));
+        build_queue_count.remove(&unit_type);
+    }
 
-    build_queue_count.remove(&unit_type);
+    cmds.spawn((
+        unit,
+        Deploying,
+        NewUnit::new(
+            vehicle_transform.translation,
+            rally_point.map(|rally| rally.0),
+        ),
+    ));
 }
 
 fn move_vehicle_from_garage(
@@ -110,7 +164,13 @@ fn move_vehicle_from_garage(
         let distance_traveled = tf.translation.distance(new_unit.start_pos);
         if distance_traveled >= 50.0 {
             vel.linvel = Vec3::ZERO;
-            cmds.entity(entity).remove::<NewUnit>();
+            cmds.entity(entity).remove::<(NewUnit, Deploying)>();
+
+            // the rally point gets its own goal field, so the new unit paths around structures
+            if let Some(rally_point) = new_unit.rally_point {
+                cmds.entity(entity)
+                    .insert((pf_comps::Destination, PathTo(rally_point)));
+            }
         }
     }
 }
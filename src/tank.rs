@@ -1,10 +1,10 @@
 use bevy::math::f32;
-use bevy::time::common_conditions::once_after_delay;
 use std::f32::consts::PI;
-use std::time::Duration;
 
 // use crate::asset_manager::audio::MyAudio;
 use crate::asset_manager::models::MyModels;
+use crate::game_state::GameState;
+use crate::teams::Team;
 use crate::units::components::*;
 use crate::*;
 
@@ -15,9 +15,13 @@ pub struct TankPlugin;
 impl Plugin for TankPlugin {
     fn build(&self, app: &mut App) {
         // app.add_systems(Startup, _spawn_tank);
+        // only when the match starts, not every time it's resumed from pause
         app.add_systems(
-            Update,
-            (spawn_tanks.run_if(once_after_delay(Duration::from_secs(1))),).chain(),
+            OnTransition {
+                exited: GameState::Loading,
+                entered: GameState::InGame,
+            },
+            spawn_tanks,
         );
     }
 }
@@ -37,7 +41,7 @@ pub fn _spawn_tank(
         rotation: tank_rotation,
         scale: Vec3::ONE,
     };
-    cmds.spawn(UnitType::TankGen1.build(transform, &my_models));
+    cmds.spawn(UnitType::TankGen1.build(transform, Team::Player, &my_models));
 
     // GEN II: Another tank with the same rotation.
     let transform = Transform {
@@ -45,7 +49,7 @@ pub fn _spawn_tank(
         rotation: tank_rotation,
         scale: Vec3::ONE,
     };
-    cmds.spawn(UnitType::TankGen2.build(transform, &my_models));
+    cmds.spawn(UnitType::TankGen2.build(transform, Team::Player, &my_models));
 
     // GEN II: And one more tank with the rotation.
     let transform = Transform {
@@ -53,7 +57,7 @@ pub fn _spawn_tank(
         rotation: tank_rotation,
         scale: Vec3::ONE,
     };
-    cmds.spawn(UnitType::TankGen2.build(transform, &my_models));
+    cmds.spawn(UnitType::TankGen2.build(transform, Team::Player, &my_models));
 }
 
 pub fn spawn_tanks(
@@ -73,7 +77,7 @@ pub fn spawn_tanks(
         let tank_rotation = Quat::from_rotation_y(-PI * 0.5);
         let mut transform = Transform::from_translation(pos);
         transform.rotation = tank_rotation;
-        UnitType::TankGen1.build(transform, &my_models)
+        UnitType::TankGen1.build(transform, Team::Player, &my_models)
     };
 
     // Create tank on the right side facing left
@@ -82,7 +86,7 @@ pub fn spawn_tanks(
         let tank_rotation = Quat::from_rotation_y(PI * 0.5);
         let mut transform = Transform::from_translation(pos);
         transform.rotation = tank_rotation;
-        UnitType::TankGen1.build(transform, &my_models)
+        UnitType::TankGen1.build(transform, Team::Enemy, &my_models)
     };
 
     // Spawn Left Group (facing right)
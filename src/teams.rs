@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+use bevy::scene::SceneInstanceReady;
+use std::collections::HashMap;
+
+pub struct TeamsPlugin;
+
+impl Plugin for TeamsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TeamMaterials>()
+            .add_observer(obs_apply_team_color);
+    }
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Team {
+    #[default]
+    Player,
+    Enemy,
+}
+
+impl Team {
+    pub fn color(&self) -> Color {
+        match self {
+            Team::Player => Color::srgb(0.15, 0.45, 0.95),
+            Team::Enemy => Color::srgb(0.9, 0.15, 0.15),
+        }
+    }
+
+    // the team controlled by the person at this computer
+    pub fn local() -> Team {
+        Team::Player
+    }
+
+    pub fn is_local(&self) -> bool {
+        *self == Team::local()
+    }
+
+    pub fn is_enemy(&self, other: &Team) -> bool {
+        self != other
+    }
+}
+
+// cache of team tinted copies, so every tank of a team shares the same materials
+#[derive(Resource, Default)]
+struct TeamMaterials(HashMap<(AssetId<StandardMaterial>, Team), Handle<StandardMaterial>>);
+
+// tint the glTF materials once the scene (unit model or placed structure) has been spawned
+fn obs_apply_team_color(
+    trigger: Trigger<SceneInstanceReady>,
+    q_team: Query<&Team>,
+    q_children: Query<&Children>,
+    mut q_material: Query<&mut MeshMaterial3d<StandardMaterial>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut team_materials: ResMut<TeamMaterials>,
+) {
+    let scene_ent = trigger.target();
+
+    let Ok(team) = q_team.get(scene_ent) else {
+        return;
+    };
+
+    for child_ent in q_children.iter_descendants(scene_ent) {
+        let Ok(mut material) = q_material.get_mut(child_ent) else {
+            continue;
+        };
+
+        let key = (material.0.id(), *team);
+        if let Some(tinted) = team_materials.0.get(&key) {
+            material.0 = tinted.clone();
+            continue;
+        }
+
+        let Some(original) = materials.get(&material.0) else {
+            continue;
+        };
+
+        let mut tinted = original.clone();
+        tinted.base_color = tinted.base_color.mix(&team.color(), 0.35);
+
+        let handle = materials.add(tinted);
+        team_materials.0.insert(key, handle.clone());
+        team_materials
+            .0
+            .insert((handle.id(), *team), handle.clone());
+        material.0 = handle;
+    }
+}
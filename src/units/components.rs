@@ -2,18 +2,22 @@ use bevy::prelude::*;
 // use bevy_kira_audio::prelude::*;
 use bevy_rapier3d::prelude::*;
 use bevy_rts_pathfinding::components as pf_comps;
+use std::collections::VecDeque;
 use strum_macros::{EnumIter, EnumString};
 
 // use crate::asset_manager::audio::*;
 use crate::asset_manager::imgs::MyImgs;
 use crate::asset_manager::models::MyModels;
+use crate::combat::components::{Health, Trajectory, Weapon};
 use crate::structures::components::StructureType;
 use crate::tank::*;
+use crate::teams::Team;
 use crate::*;
 
 const TANK_GEN_1_SIZE: Vec3 = Vec3::new(6.5, 3.1, 10.75);
 const TANK_GEN_2_SIZE: Vec3 = Vec3::new(7.5, 3.1, 13.0);
 const ARTILLERY_SIZE: Vec3 = Vec3::new(7.5, 3.1, 16.0);
+const HARVESTER_SIZE: Vec3 = Vec3::new(7.5, 3.5, 12.0);
 
 #[derive(Component, Clone)]
 pub struct UnitSelectBorder(pub Entity);
@@ -33,6 +37,110 @@ pub struct SelectionBox;
 #[derive(Component, Default)]
 pub struct IsMoving(pub bool);
 
+// Drive straight to a world position, without building a flow field (taking a formation slot,
+// etc.)
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MoveTo(pub Vec3);
+
+// Path to a world position over a goal field, for moves whose goal isn't under the cursor (rally
+// points, queued waypoints, etc.). comes with a Destination, like a flow field move
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PathTo(pub Vec3);
+
+// where a move order's flow field leads, the flow field itself doesn't keep it around
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MoveGoal(pub Vec3);
+
+// Shift+click waypoints, moved to one after another once the current move order is done
+#[derive(Component, Debug, Default)]
+pub struct OrderQueue(pub VecDeque<Vec3>);
+
+// where a unit ends up in a group move, and the pace of the group's slowest unit
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FormationSlot {
+    pub pos: Vec3,
+    pub speed: f32,
+}
+
+// stays put, only firing at whatever comes into range. stopped units chase a target that backs
+// out of range, these let it go
+#[derive(Component)]
+pub struct HoldPosition;
+
+// back and forth between two points, heading for points[next]
+#[derive(Component, Debug)]
+pub struct Patrol {
+    pub points: [Vec3; 2],
+    pub next: usize,
+}
+
+impl Patrol {
+    pub fn new(from: Vec3, to: Vec3) -> Self {
+        Self {
+            points: [from, to],
+            next: 1,
+        }
+    }
+
+    pub fn turn_around(&mut self) -> Vec3 {
+        self.next = 1 - self.next;
+        self.points[self.next]
+    }
+}
+
+// follows a friendly unit or structure around, attacking anything that gets close to it
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Guard(pub Entity);
+
+// heading for a point, but stopping to fight any enemy met along the way
+#[derive(Component, Debug, Clone, Copy)]
+pub struct AttackMove(pub Vec3);
+
+// orders on the command interface, on top of the move and attack orders given by clicking
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub enum UnitOrder {
+    Stop,
+    HoldPosition,
+    Patrol,
+    Guard,
+    AttackMove,
+}
+
+impl UnitOrder {
+    // the arrow keys pan the camera
+    pub fn key(&self) -> KeyCode {
+        match self {
+            UnitOrder::Stop => KeyCode::KeyS,
+            UnitOrder::HoldPosition => KeyCode::KeyH,
+            UnitOrder::Patrol => KeyCode::KeyP,
+            UnitOrder::Guard => KeyCode::KeyG,
+            UnitOrder::AttackMove => KeyCode::KeyA,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            UnitOrder::Stop => "Stop (S)",
+            UnitOrder::HoldPosition => "Hold (H)",
+            UnitOrder::Patrol => "Patrol (P)",
+            UnitOrder::Guard => "Guard (G)",
+            UnitOrder::AttackMove => "Attack (A)",
+        }
+    }
+
+    // these wait on the next click, for where to go or who to guard
+    pub fn needs_target(&self) -> bool {
+        matches!(
+            self,
+            UnitOrder::Patrol | UnitOrder::Guard | UnitOrder::AttackMove
+        )
+    }
+}
+
+// a freshly built unit that is still driving out of its structure
+#[derive(Component)]
+pub struct Deploying;
+
 #[derive(Component)]
 #[require(pf_comps::RtsObj, IsMoving, Velocity)]
 pub struct Unit;
@@ -47,6 +155,8 @@ pub enum UnitType {
     TankGen2,
     #[strum(serialize = "artillery")]
     Artillery,
+    #[strum(serialize = "harvester")]
+    Harvester,
 }
 
 impl UnitType {
@@ -56,6 +166,7 @@ impl UnitType {
             UnitType::TankGen1 => StructureType::VehicleDepot,
             UnitType::TankGen2 => StructureType::VehicleDepot,
             UnitType::Artillery => StructureType::VehicleDepot,
+            UnitType::Harvester => StructureType::VehicleDepot,
         }
     }
 
@@ -65,6 +176,7 @@ impl UnitType {
             UnitType::TankGen1 => 100,
             UnitType::TankGen2 => 200,
             UnitType::Artillery => 150,
+            UnitType::Harvester => 300,
         }
     }
 
@@ -74,15 +186,110 @@ impl UnitType {
             UnitType::TankGen1 => SPEED_TANK_GEN_1,
             UnitType::TankGen2 => SPEED_TANK_GEN_2,
             UnitType::Artillery => SPEED_ARTILLERY,
+            UnitType::Harvester => SPEED_HARVESTER,
         }
     }
 
-    pub fn dmg(&self) -> i32 {
+    pub fn dmg(&self) -> Option<i32> {
         match self {
-            UnitType::Rifleman => 1,
-            UnitType::TankGen1 => 10,
-            UnitType::TankGen2 => 20,
-            UnitType::Artillery => 30,
+            UnitType::Rifleman => Some(1),
+            UnitType::TankGen1 => Some(10),
+            UnitType::TankGen2 => Some(20),
+            UnitType::Artillery => Some(30),
+            _ => None,
+        }
+    }
+
+    pub fn range(&self) -> Option<f32> {
+        match self {
+            UnitType::Rifleman => Some(60.0),
+            UnitType::TankGen1 => Some(100.0),
+            UnitType::TankGen2 => Some(110.0),
+            UnitType::Artillery => Some(250.0),
+            _ => None,
+        }
+    }
+
+    // seconds between shots
+    pub fn rate_of_fire(&self) -> Option<f32> {
+        match self {
+            UnitType::Rifleman => Some(0.5),
+            UnitType::TankGen1 => Some(1.5),
+            UnitType::TankGen2 => Some(1.25),
+            UnitType::Artillery => Some(4.0),
+            _ => None,
+        }
+    }
+
+    pub fn projectile_speed(&self) -> Option<f32> {
+        match self {
+            UnitType::Rifleman => Some(300.0),
+            UnitType::TankGen1 => Some(200.0),
+            UnitType::TankGen2 => Some(220.0),
+            UnitType::Artillery => Some(120.0),
+            _ => None,
+        }
+    }
+
+    // where projectiles leave the model, relative to the unit's origin (forward is -Z)
+    pub fn muzzle_offset(&self) -> Option<Vec3> {
+        match self {
+            UnitType::Rifleman => Some(Vec3::new(0.0, 2.0, -1.0)),
+            UnitType::TankGen1 => Some(Vec3::new(0.0, 2.5, -8.0)),
+            UnitType::TankGen2 => Some(Vec3::new(0.0, 2.5, -9.0)),
+            UnitType::Artillery => Some(Vec3::new(0.0, 3.0, -10.0)),
+            _ => None,
+        }
+    }
+
+    // unarmed units (harvesters) ignore attack orders
+    pub fn is_armed(&self) -> bool {
+        self.dmg().is_some()
+    }
+
+    pub fn trajectory(&self) -> Trajectory {
+        match self {
+            UnitType::Artillery => Trajectory::Ballistic,
+            _ => Trajectory::Direct,
+        }
+    }
+
+    pub fn weapon(&self) -> Option<Weapon> {
+        let (
+            Some(dmg),
+            Some(range),
+            Some(rate_of_fire),
+            Some(projectile_speed),
+            Some(muzzle_offset),
+        ) = (
+            self.dmg(),
+            self.range(),
+            self.rate_of_fire(),
+            self.projectile_speed(),
+            self.muzzle_offset(),
+        )
+        else {
+            return None;
+        };
+
+        Some(Weapon::new(
+            dmg,
+            range,
+            rate_of_fire,
+            projectile_speed,
+            muzzle_offset,
+            self.trajectory(),
+        ))
+    }
+
+    // how far the unit can see through the fog of war
+    pub fn sight(&self) -> f32 {
+        match self {
+            UnitType::Rifleman => 80.0,
+            UnitType::TankGen1 => 120.0,
+            UnitType::TankGen2 => 130.0,
+            UnitType::Artillery => 100.0,
+            UnitType::Harvester => 60.0,
         }
     }
 
@@ -92,6 +299,7 @@ impl UnitType {
             UnitType::TankGen1 => 3,
             UnitType::TankGen2 => 3,
             UnitType::Artillery => 3,
+            UnitType::Harvester => 5,
         }
     }
 
@@ -101,6 +309,7 @@ impl UnitType {
             UnitType::TankGen1 => 500,
             UnitType::TankGen2 => 800,
             UnitType::Artillery => 650,
+            UnitType::Harvester => 800,
         }
     }
 
@@ -110,6 +319,7 @@ impl UnitType {
             UnitType::TankGen1 => "Tank Gen I".to_string(),
             UnitType::TankGen2 => "Tank Gen II".to_string(),
             UnitType::Artillery => "Artillery".to_string(),
+            UnitType::Harvester => "Harvester".to_string(),
         }
     }
 
@@ -119,6 +329,7 @@ impl UnitType {
             UnitType::TankGen1 => my_imgs.unit_tank_gen_1.clone(),
             UnitType::TankGen2 => my_imgs.unit_tank_gen_2.clone(),
             UnitType::Artillery => my_imgs.unit_artillery.clone(),
+            UnitType::Harvester => my_imgs.unit_harvester.clone(),
         }
     }
 
@@ -128,15 +339,17 @@ impl UnitType {
             UnitType::TankGen1 => my_models.tank_gen1.clone(),
             UnitType::TankGen2 => my_models.tank_gen2.clone(),
             UnitType::Artillery => my_models.artillery.clone(),
+            UnitType::Harvester => my_models.harvester.clone(),
         }
     }
 
-    fn size(&self) -> Vec3 {
+    pub fn size(&self) -> Vec3 {
         match self {
             UnitType::Rifleman => Vec3::new(2.0, 2.0, 2.0), // TODO: Define rifleman size
             UnitType::TankGen1 => TANK_GEN_1_SIZE,
             UnitType::TankGen2 => TANK_GEN_2_SIZE,
             UnitType::Artillery => ARTILLERY_SIZE,
+            UnitType::Harvester => HARVESTER_SIZE,
         }
     }
 
@@ -156,6 +369,7 @@ impl UnitType {
     pub fn build(
         &self,
         transform: Transform,
+        team: Team,
         my_models: &Res<MyModels>,
         // audio: &bevy_kira_audio::Audio,
         // my_audio: &MyAudio,
@@ -165,6 +379,7 @@ impl UnitType {
             self.model(&my_models),
             self.size(),
             transform,
+            team,
             *self,
             // self.audio_emitter(&audio, &my_audio),
         );
@@ -178,12 +393,14 @@ pub struct UnitBundle {
     pub border_size: BorderSize,
     pub collider: Collider,
     pub damping: Damping,
+    pub health: Health,
     pub locked_axis: LockedAxes,
     pub mass_properties: ColliderMassProperties, // TODO: remove
     pub name: Name,
     pub rigid_body: RigidBody,
     pub scene_root: SceneRoot,
     pub speed: Speed,
+    pub team: Team,
     pub transform: Transform,
     pub transform_global: GlobalTransform,
     pub unit_type: UnitType,
@@ -198,6 +415,7 @@ impl UnitBundle {
         scene: Handle<Scene>,
         size: Vec3,
         transform: Transform,
+        team: Team,
         unit_type: UnitType,
         // audio_emitter: SpatialAudioEmitter,
     ) -> Self {
@@ -209,6 +427,7 @@ impl UnitBundle {
                 angular_damping: 20.0,
                 ..default()
             },
+            health: Health::new(unit_type.hp()),
             name: Name::new(unit_type.name()),
             locked_axis: (LockedAxes::ROTATION_LOCKED_X
                 | LockedAxes::ROTATION_LOCKED_Z
@@ -222,6 +441,7 @@ impl UnitBundle {
             rigid_body: RigidBody::KinematicVelocityBased,
             scene_root: SceneRoot(scene),
             speed: Speed(unit_type.speed()),
+            team,
             transform,
             transform_global: GlobalTransform::default(),
             unit_type: unit_type,
@@ -1,9 +1,17 @@
 use bevy::prelude::*;
 
+use crate::teams::Team;
+
 use super::components::UnitType;
 
 #[derive(Event)]
-pub struct QueueVehicleEv(pub UnitType);
+pub struct QueueVehicleEv {
+    pub unit: UnitType,
+    pub team: Team,
+}
 
 #[derive(Event)]
-pub struct QueueSolderEv(pub UnitType);
+pub struct QueueSolderEv {
+    pub unit: UnitType,
+    pub team: Team,
+}
@@ -0,0 +1,208 @@
+use bevy::prelude::*;
+use bevy_rts_pathfinding::components as pf_comps;
+
+use super::components::{FormationSlot, MoveTo, OrderQueue, UnitType};
+use super::resources::Formation;
+use crate::game_state::GameState;
+
+const SLOT_GAP: f32 = 4.0;
+const SLOT_HANDOFF_DIST: f32 = 40.0; // how close to its slot a unit leaves the flow field
+
+pub struct FormationsPlugin;
+
+impl Plugin for FormationsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (cycle_formation, take_formation_slots, leave_formation)
+                .chain()
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+fn cycle_formation(keys: Res<ButtonInput<KeyCode>>, mut formation: ResMut<Formation>) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if !ctrl && keys.just_pressed(KeyCode::KeyF) {
+        *formation = formation.next();
+    }
+}
+
+// gives each unit its own slot around the center, so a group doesn't bunch up on a single point.
+// big units take the front rows, and within a row units keep to their side to avoid crossing paths
+pub fn assign_slots(
+    formation: Formation,
+    center: Vec3,
+    facing: Option<Vec3>,
+    units: &[(Entity, Vec3, UnitType)],
+) -> Vec<(Entity, FormationSlot)> {
+    if units.is_empty() {
+        return Vec::new();
+    }
+
+    // without a drag, the group faces the way it's heading
+    let centroid = units.iter().map(|(_, pos, _)| *pos).sum::<Vec3>() / units.len() as f32;
+    let forward = facing
+        .unwrap_or(center - centroid)
+        .with_y(0.0)
+        .try_normalize()
+        .unwrap_or(Vec3::NEG_Z);
+    let right = forward.cross(Vec3::Y);
+
+    let spacing = units
+        .iter()
+        .map(|(_, _, unit_type)| unit_type.size().x.max(unit_type.size().z))
+        .fold(0.0, f32::max)
+        + SLOT_GAP;
+
+    let speed = units
+        .iter()
+        .map(|(_, _, unit_type)| unit_type.speed())
+        .fold(f32::MAX, f32::min);
+
+    let mut sorted = units.to_vec();
+    sorted.sort_by(|(_, _, a), (_, _, b)| {
+        let a_size = a.size().x * a.size().z;
+        let b_size = b.size().x * b.size().z;
+        b_size
+            .total_cmp(&a_size)
+            .then(a.speed().total_cmp(&b.speed()))
+    });
+
+    let lateral = |pos: Vec3| (pos - center).dot(right);
+
+    let mut slots = Vec::with_capacity(units.len());
+    let mut remaining = sorted.into_iter();
+    for mut row in formation.rows(units.len()) {
+        let mut row_units: Vec<(Entity, Vec3, UnitType)> =
+            remaining.by_ref().take(row.len()).collect();
+        row_units.sort_by(|(_, a, _), (_, b, _)| lateral(*a).total_cmp(&lateral(*b)));
+        row.sort_by(|a, b| a.x.total_cmp(&b.x));
+
+        for ((unit_ent, _, _), offset) in row_units.into_iter().zip(row) {
+            let pos = center + (right * offset.x + forward * offset.y) * spacing;
+            slots.push((unit_ent, FormationSlot { pos, speed }));
+        }
+    }
+
+    slots
+}
+
+// units follow the flow field until they're close to their slot, then drive straight into it
+fn take_formation_slots(
+    mut cmds: Commands,
+    mut removed_destination: RemovedComponents<pf_comps::Destination>,
+    q_unit: Query<
+        (
+            Entity,
+            &Transform,
+            &FormationSlot,
+            Has<pf_comps::Destination>,
+            Has<MoveTo>,
+        ),
+        Without<OrderQueue>,
+    >,
+) {
+    // the flow field gave out short of the slot
+    for unit_ent in removed_destination.read() {
+        let Ok((_, _, slot, has_destination, has_move_to)) = q_unit.get(unit_ent) else {
+            continue;
+        };
+
+        if !has_destination && !has_move_to {
+            cmds.entity(unit_ent).insert(MoveTo(slot.pos));
+        }
+    }
+
+    for (unit_ent, trans, slot, has_destination, _) in q_unit.iter() {
+        if has_destination && trans.translation.xz().distance(slot.pos.xz()) <= SLOT_HANDOFF_DIST {
+            cmds.entity(unit_ent)
+                .remove::<pf_comps::Destination>()
+                .insert(MoveTo(slot.pos));
+        }
+    }
+}
+
+// back to the unit's own pace once it's in its slot
+fn leave_formation(
+    mut cmds: Commands,
+    mut removed_move_to: RemovedComponents<MoveTo>,
+    q_unit: Query<
+        (),
+        (
+            With<FormationSlot>,
+            Without<MoveTo>,
+            Without<pf_comps::Destination>,
+        ),
+    >,
+) {
+    for unit_ent in removed_move_to.read() {
+        if q_unit.contains(unit_ent) {
+            cmds.entity(unit_ent).remove::<FormationSlot>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn units(types: &[UnitType]) -> Vec<(Entity, Vec3, UnitType)> {
+        types
+            .iter()
+            .enumerate()
+            .map(|(i, unit_type)| (Entity::from_raw(i as u32), Vec3::ZERO, *unit_type))
+            .collect()
+    }
+
+    #[test]
+    fn every_unit_gets_one_slot() {
+        for formation in [Formation::Line, Formation::Box, Formation::Wedge] {
+            for n in 1..=12 {
+                let units = units(&vec![UnitType::Rifleman; n]);
+                let slots = assign_slots(formation, Vec3::ZERO, Some(Vec3::NEG_Z), &units);
+
+                assert_eq!(slots.len(), n, "{formation:?} with {n} units");
+                for (unit_ent, _, _) in units.iter() {
+                    let count = slots.iter().filter(|(ent, _)| ent == unit_ent).count();
+                    assert_eq!(count, 1, "{formation:?} with {n} units");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn line_spreads_across_the_facing() {
+        let units = units(&[UnitType::Rifleman; 4]);
+        let center = Vec3::new(50.0, 0.0, -20.0);
+        let slots = assign_slots(Formation::Line, center, Some(Vec3::X), &units);
+
+        // facing along x, so the line runs along z through the center
+        for (_, slot) in slots.iter() {
+            assert!((slot.pos.x - center.x).abs() < 1e-3);
+        }
+
+        let spread = slots
+            .iter()
+            .map(|(_, slot)| slot.pos.z - center.z)
+            .fold(0.0, |acc: f32, z| acc.max(z.abs()));
+        assert!(spread > 0.0);
+    }
+
+    #[test]
+    fn big_units_lead_and_the_group_keeps_the_slowest_pace() {
+        let units = units(&[UnitType::Rifleman, UnitType::TankGen1, UnitType::Rifleman]);
+        let slots = assign_slots(Formation::Wedge, Vec3::ZERO, Some(Vec3::NEG_Z), &units);
+
+        // the tank takes the tip of the wedge, furthest along the facing
+        let front = slots
+            .iter()
+            .max_by(|(_, a), (_, b)| a.pos.dot(Vec3::NEG_Z).total_cmp(&b.pos.dot(Vec3::NEG_Z)))
+            .map(|(ent, _)| *ent);
+        assert_eq!(front, Some(units[1].0));
+
+        for (_, slot) in slots.iter() {
+            assert_eq!(slot.speed, UnitType::Rifleman.speed());
+        }
+    }
+}
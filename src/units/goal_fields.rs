@@ -0,0 +1,223 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use bevy_rts_pathfinding::components as pf_comps;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use super::components::{FormationSlot, PathTo, Speed};
+use super::steer_towards;
+use crate::game_state::GameState;
+use crate::structures::components::{Structure, StructurePlaceholder};
+use crate::{CELL_SIZE, MAP_DEPTH, MAP_GRID_COLUMNS, MAP_GRID_ROWS, MAP_WIDTH};
+
+const ARRIVE_RADIUS: f32 = 5.0;
+const MAX_FIELDS: usize = 64; // goals remembered before the cache starts over
+
+// (offset, cost) to each of a cell's neighbours, diagonals cost more
+const NEIGHBOURS: [(IVec2, u32); 8] = [
+    (IVec2::new(1, 0), 10),
+    (IVec2::new(-1, 0), 10),
+    (IVec2::new(0, 1), 10),
+    (IVec2::new(0, -1), 10),
+    (IVec2::new(1, 1), 14),
+    (IVec2::new(1, -1), 14),
+    (IVec2::new(-1, 1), 14),
+    (IVec2::new(-1, -1), 14),
+];
+
+pub struct GoalFieldsPlugin;
+
+impl Plugin for GoalFieldsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GoalFields>().add_systems(
+            Update,
+            (mark_blocked_cells, path_to_goal)
+                .chain()
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+// flow fields towards goals that aren't under the cursor (rally points, waypoints, etc.). the
+// pathfinding plugin only builds fields towards the cursor, so these go over the same grid
+// here, one field per goal cell, shared by every unit heading there
+#[derive(Resource, Default)]
+struct GoalFields {
+    blocked: HashSet<IVec2>,
+    fields: HashMap<IVec2, Vec<u32>>, // cost to the goal cell from every cell
+}
+
+impl GoalFields {
+    // the (column, row) a world position falls into
+    fn cell(pos: Vec3) -> IVec2 {
+        let col = ((pos.x + MAP_WIDTH / 2.0) / CELL_SIZE).floor() as i32;
+        let row = ((pos.z + MAP_DEPTH / 2.0) / CELL_SIZE).floor() as i32;
+        IVec2::new(
+            col.clamp(0, MAP_GRID_COLUMNS - 1),
+            row.clamp(0, MAP_GRID_ROWS - 1),
+        )
+    }
+
+    fn center(cell: IVec2) -> Vec3 {
+        Vec3::new(
+            (cell.x as f32 + 0.5) * CELL_SIZE - MAP_WIDTH / 2.0,
+            0.0,
+            (cell.y as f32 + 0.5) * CELL_SIZE - MAP_DEPTH / 2.0,
+        )
+    }
+
+    fn idx(cell: IVec2) -> usize {
+        (cell.y * MAP_GRID_COLUMNS + cell.x) as usize
+    }
+
+    fn in_grid(cell: IVec2) -> bool {
+        cell.x >= 0 && cell.y >= 0 && cell.x < MAP_GRID_COLUMNS && cell.y < MAP_GRID_ROWS
+    }
+
+    // diagonal steps past the corner of a structure would clip it
+    fn cuts_corner(&self, cell: IVec2, offset: IVec2) -> bool {
+        offset.x != 0
+            && offset.y != 0
+            && (self.blocked.contains(&(cell + IVec2::new(offset.x, 0)))
+                || self.blocked.contains(&(cell + IVec2::new(0, offset.y))))
+    }
+
+    fn integrate(&self, goal: IVec2) -> Vec<u32> {
+        let mut costs = vec![u32::MAX; (MAP_GRID_COLUMNS * MAP_GRID_ROWS) as usize];
+        let mut open = BinaryHeap::new();
+
+        costs[Self::idx(goal)] = 0;
+        open.push(Reverse((0, goal.x, goal.y)));
+
+        while let Some(Reverse((cost, col, row))) = open.pop() {
+            let cell = IVec2::new(col, row);
+            if cost > costs[Self::idx(cell)] {
+                continue;
+            }
+
+            for (offset, step) in NEIGHBOURS {
+                let next = cell + offset;
+                if !Self::in_grid(next)
+                    || self.blocked.contains(&next)
+                    || self.cuts_corner(cell, offset)
+                {
+                    continue;
+                }
+
+                let next_cost = cost + step;
+                if next_cost < costs[Self::idx(next)] {
+                    costs[Self::idx(next)] = next_cost;
+                    open.push(Reverse((next_cost, next.x, next.y)));
+                }
+            }
+        }
+
+        costs
+    }
+
+    // which way to head from pos to reach the goal around structures. downhill through the field
+    // cell by cell, then straight at the goal once in its cell (or if there's no way around)
+    fn steering(&mut self, pos: Vec3, goal: Vec3) -> Vec3 {
+        let from = Self::cell(pos);
+        let to = Self::cell(goal);
+        if from == to {
+            return goal - pos;
+        }
+
+        if !self.fields.contains_key(&to) {
+            if self.fields.len() >= MAX_FIELDS {
+                self.fields.clear();
+            }
+
+            let field = self.integrate(to);
+            self.fields.insert(to, field);
+        }
+
+        let field = &self.fields[&to];
+        let next = NEIGHBOURS
+            .iter()
+            .map(|(offset, _)| (from + *offset, *offset))
+            .filter(|(cell, offset)| Self::in_grid(*cell) && !self.cuts_corner(from, *offset))
+            .map(|(cell, _)| cell)
+            .min_by_key(|cell| field[Self::idx(*cell)]);
+
+        match next {
+            Some(cell) if field[Self::idx(cell)] < field[Self::idx(from)] => {
+                Self::center(cell) - pos
+            }
+            _ => goal - pos,
+        }
+    }
+}
+
+// structures block every cell their footprint covers, which throws out all the old fields
+fn mark_blocked_cells(
+    mut goal_fields: ResMut<GoalFields>,
+    q_added: Query<(), (Added<Structure>, Without<StructurePlaceholder>)>,
+    mut removed: RemovedComponents<Structure>,
+    q_structure: Query<
+        (&Transform, &pf_comps::RtsObjSize),
+        (With<Structure>, Without<StructurePlaceholder>),
+    >,
+) {
+    let removed_any = removed.read().count() > 0;
+    if q_added.is_empty() && !removed_any {
+        return;
+    }
+
+    goal_fields.blocked.clear();
+    goal_fields.fields.clear();
+
+    for (trans, size) in q_structure.iter() {
+        let half = size.0 / 2.0;
+        let center = GoalFields::cell(trans.translation);
+        let reach = (half.xz().length() / CELL_SIZE).ceil() as i32;
+
+        for row in (center.y - reach).max(0)..=(center.y + reach).min(MAP_GRID_ROWS - 1) {
+            for col in (center.x - reach).max(0)..=(center.x + reach).min(MAP_GRID_COLUMNS - 1) {
+                let cell = IVec2::new(col, row);
+
+                // structures can be turned, so check the cell against the footprint's own axes
+                let local =
+                    trans.rotation.inverse() * (GoalFields::center(cell) - trans.translation);
+                if local.x.abs() <= half.x && local.z.abs() <= half.z {
+                    goal_fields.blocked.insert(cell);
+                }
+            }
+        }
+    }
+}
+
+fn path_to_goal(
+    mut cmds: Commands,
+    mut goal_fields: ResMut<GoalFields>,
+    mut q_unit: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Velocity,
+            &Speed,
+            &PathTo,
+            Option<&FormationSlot>,
+        ),
+        With<pf_comps::Destination>,
+    >,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+
+    for (ent, mut tx, mut vel, speed, path_to, slot) in q_unit.iter_mut() {
+        if tx.translation.xz().distance(path_to.0.xz()) <= ARRIVE_RADIUS {
+            vel.linvel = Vec3::ZERO;
+            cmds.entity(ent).remove::<(pf_comps::Destination, PathTo)>();
+            continue;
+        }
+
+        let mut steering = goal_fields.steering(tx.translation, path_to.0);
+        steering.y = 0.0;
+
+        // a group in formation keeps to the pace of its slowest unit
+        let speed = slot.map_or(speed.0, |slot| slot.speed);
+        steer_towards(&mut tx, &mut vel, steering, speed, dt);
+    }
+}
@@ -4,57 +4,83 @@ use bevy_rapier3d::prelude::Velocity;
 use bevy_rts_pathfinding::components as pf_comps;
 use bevy_rts_pathfinding::events as pf_events;
 use bevy_rts_pathfinding::flowfield::FlowField;
-use components::{IsMoving, SelectedUnit, Speed, UnitType};
+use components::{
+    AttackMove, FormationSlot, Guard, HoldPosition, IsMoving, MoveGoal, MoveTo, OrderQueue, PathTo,
+    Patrol, SelectedUnit, Speed, UnitType,
+};
 use events::{QueueSolderEv, QueueVehicleEv};
+use std::collections::VecDeque;
 
 use crate::cmd_interface::events::BuildUnitEv;
-use crate::events::SetUnitDestinationEv;
+use crate::combat::components::{AttackOrder, Target, Weapon};
+use crate::events::{AttackTargetEv, SetUnitDestinationEv};
+use crate::game_state::GameState;
+use crate::research::components::ResearchType;
+use crate::research::resources::CompletedResearch;
 use crate::resources::{DbgOptions, MouseCoords};
 use crate::structures::components::*;
 use crate::structures::resources::StructuresBuilt;
+use crate::teams::Team;
 use crate::{structures::*, utils};
 
 pub mod components;
 pub mod events;
+mod formations;
+mod goal_fields;
+mod orders;
 pub mod resources;
 
+use formations::FormationsPlugin;
+use goal_fields::GoalFieldsPlugin;
+use orders::OrdersPlugin;
 use resources::*;
 
 pub struct UnitsPlugin;
 
 impl Plugin for UnitsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(ResourcesPlugin)
-            .add_systems(
-                Update,
-                (
-                    set_is_moving,
-                    stop_movement,
-                    mark_available_units.after(count_structures),
-                    move_unit.run_if(any_with_component::<pf_comps::Destination>),
+        app.add_plugins((
+            ResourcesPlugin,
+            OrdersPlugin,
+            FormationsPlugin,
+            GoalFieldsPlugin,
+        ))
+        .add_systems(
+            Update,
+            (
+                set_is_moving,
+                stop_movement,
+                mark_available_units.after(count_structures).run_if(
+                    resource_changed::<StructuresBuilt>.or(resource_changed::<CompletedResearch>),
                 ),
+                move_unit.run_if(any_with_component::<pf_comps::Destination>),
+                move_to_point.run_if(any_with_component::<MoveTo>),
+                advance_order_queue.after(move_to_point),
+                draw_order_queues,
             )
-            .add_observer(set_unit_destination)
-            .add_observer(handle_build_unit);
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_observer(set_unit_destination)
+        .add_observer(set_unit_attack_target)
+        .add_observer(handle_build_unit);
     }
 }
 
+// re-evaluated whenever a structure is built or destroyed (or research completes), so losing the last depot locks its units again
 fn mark_available_units(
-    q_structures: Query<&Structure, Added<Structure>>,
     structures_built: Res<StructuresBuilt>,
+    completed_research: Res<CompletedResearch>,
     mut available_units: ResMut<UnlockedUnits>,
 ) {
-    for _structure in q_structures.iter() {
-        if structures_built.vehicle_depot > 0 {
-            available_units.tank_gen1 = true;
-            available_units.tank_gen2 = true; // TODO: requrie research eventually
-            available_units.artillery = true;
-        }
+    let has_vehicle_depot = structures_built.vehicle_depot > 0;
+    available_units.tank_gen1 = has_vehicle_depot;
+    available_units.tank_gen2 =
+        has_vehicle_depot && completed_research.contains(ResearchType::TankGen2);
+    available_units.artillery = has_vehicle_depot;
 
-        if structures_built.barracks > 0 {
-            available_units.rifleman = true;
-        }
-    }
+    available_units.harvester = has_vehicle_depot && structures_built.refinery > 0;
+
+    available_units.rifleman = structures_built.barracks > 0;
 }
 
 // this consumes the BuildUnitEv, and determines which units to build (from vehicle depot or barracks)
@@ -64,45 +90,236 @@ fn handle_build_unit(trigger: Trigger<BuildUnitEv>, mut cmds: Commands, dbg: Res
     dbg.print(&format!("Building unit: {}", unit_type.name()));
 
     match unit_type.source() {
-        StructureType::Barracks => cmds.trigger(QueueSolderEv(unit_type)),
-        StructureType::VehicleDepot => cmds.trigger(QueueVehicleEv(unit_type)),
+        StructureType::Barracks => cmds.trigger(QueueSolderEv {
+            unit: unit_type,
+            team: Team::local(),
+        }),
+        StructureType::VehicleDepot => cmds.trigger(QueueVehicleEv {
+            unit: unit_type,
+            team: Team::local(),
+        }),
         _ => (),
     }
 }
 
 pub fn set_unit_destination(
-    _trigger: Trigger<SetUnitDestinationEv>,
+    trigger: Trigger<SetUnitDestinationEv>,
     mouse_coords: ResMut<MouseCoords>,
-    mut q_unit: Query<Entity, With<SelectedUnit>>,
+    formation: Res<Formation>,
+    mut q_unit: Query<
+        (
+            Entity,
+            &Transform,
+            &UnitType,
+            Has<pf_comps::Destination>,
+            Has<MoveTo>,
+            Option<&mut OrderQueue>,
+        ),
+        With<SelectedUnit>,
+    >,
     q_cam: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
     read_rapier: ReadRapierContext,
+    keys: Res<ButtonInput<KeyCode>>,
     mut cmds: Commands,
 ) {
-    if !mouse_coords.in_bounds() {
+    // a drag was checked where it started, a click still needs to land on open ground
+    let dest = match trigger.center {
+        Some(center) => center,
+        None => {
+            if !mouse_coords.in_bounds() {
+                return;
+            }
+
+            let Ok(rapier_ctx) = read_rapier.single() else {
+                return;
+            };
+
+            let Ok((cam, cam_trans)) = q_cam.single() else {
+                return;
+            };
+
+            let hit = utils::cast_ray(&rapier_ctx, &cam, &cam_trans, mouse_coords.viewport);
+
+            if let Some(_) = hit {
+                return;
+            }
+
+            mouse_coords.world
+        }
+    };
+
+    let is_queued = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    let mut units = Vec::new();
+    for (unit_entity, trans, unit_type, has_destination, has_move_to, order_queue) in
+        q_unit.iter_mut()
+    {
+        // shift appends a waypoint for units that are already on the move
+        if is_queued && (has_destination || has_move_to) {
+            match order_queue {
+                Some(mut order_queue) => order_queue.0.push_back(dest),
+                None => {
+                    let waypoints = VecDeque::from([dest]);
+                    cmds.entity(unit_entity).insert(OrderQueue(waypoints));
+                }
+            }
+            continue;
+        }
+
+        // a move order overrides any attack order (and any queued waypoints or standing orders)
+        cmds.entity(unit_entity)
+            .remove::<(AttackOrder, Target, MoveTo, PathTo, OrderQueue)>()
+            .remove::<(HoldPosition, Patrol, Guard, FormationSlot, AttackMove)>()
+            .insert(pf_comps::Destination);
+
+        // the flow field leads to the cursor, a drag's start has to be pathed to on its own
+        match trigger.center {
+            Some(_) => cmds.entity(unit_entity).insert(PathTo(dest)),
+            None => cmds.entity(unit_entity).insert(MoveGoal(dest)),
+        };
+        units.push((unit_entity, trans.translation, *unit_type));
+    }
+
+    if units.is_empty() {
         return;
     }
 
-    let Ok(rapier_ctx) = read_rapier.single() else {
+    // a group spreads out into a formation around the click, instead of bunching up on it
+    if units.len() > 1 {
+        let slots = formations::assign_slots(*formation, dest, trigger.facing, &units);
+        for (unit_entity, slot) in slots {
+            cmds.entity(unit_entity).insert(slot);
+        }
+    }
+
+    if trigger.center.is_some() {
         return;
-    };
+    }
+
+    let units = units
+        .into_iter()
+        .map(|(unit_entity, _, _)| unit_entity)
+        .collect();
+    cmds.trigger(pf_events::InitializeFlowFieldEv(units));
+}
+
+// units already in range open fire, the rest path towards the target
+fn set_unit_attack_target(
+    trigger: Trigger<AttackTargetEv>,
+    mut cmds: Commands,
+    q_unit: Query<(Entity, &Transform, &Weapon, &UnitType), With<SelectedUnit>>,
+    q_target: Query<&Transform>,
+) {
+    let target_ent = trigger.0;
 
-    let Ok((cam, cam_trans)) = q_cam.single() else {
+    let Ok(target_trans) = q_target.get(target_ent) else {
         return;
     };
 
-    let hit = utils::cast_ray(&rapier_ctx, &cam, &cam_trans, mouse_coords.viewport);
+    let mut units = Vec::new();
+    for (unit_ent, unit_trans, weapon, unit_type) in q_unit.iter() {
+        if !unit_type.is_armed() {
+            continue;
+        }
+
+        cmds.entity(unit_ent)
+            .remove::<(
+                MoveGoal,
+                MoveTo,
+                PathTo,
+                OrderQueue,
+                HoldPosition,
+                Patrol,
+                Guard,
+                FormationSlot,
+                AttackMove,
+            )>()
+            .insert((AttackOrder(target_ent), Target(target_ent)));
 
-    if let Some(_) = hit {
-        return;
+        if weapon.in_range(unit_trans.translation, target_trans.translation) {
+            cmds.entity(unit_ent).remove::<pf_comps::Destination>();
+        } else {
+            cmds.entity(unit_ent).insert(pf_comps::Destination);
+            units.push(unit_ent);
+        }
     }
 
-    let mut units = Vec::new();
-    for unit_entity in q_unit.iter_mut() {
-        cmds.entity(unit_entity).insert(pf_comps::Destination);
-        units.push(unit_entity);
+    // the target is under the cursor, where the flow field leads
+    if !units.is_empty() {
+        cmds.trigger(pf_events::InitializeFlowFieldEv(units));
     }
+}
 
-    cmds.trigger(pf_events::InitializeFlowFieldEv(units));
+// once a unit finishes its current move (flow field or direct), it paths to its next waypoint
+fn advance_order_queue(
+    mut cmds: Commands,
+    mut removed_destination: RemovedComponents<pf_comps::Destination>,
+    mut removed_move_to: RemovedComponents<MoveTo>,
+    mut q_unit: Query<(
+        &mut OrderQueue,
+        Has<pf_comps::Destination>,
+        Has<MoveTo>,
+        Has<AttackOrder>,
+    )>,
+) {
+    let finished: Vec<Entity> = removed_destination
+        .read()
+        .chain(removed_move_to.read())
+        .collect();
+
+    for unit_ent in finished {
+        let Ok((mut order_queue, has_destination, has_move_to, has_attack_order)) =
+            q_unit.get_mut(unit_ent)
+        else {
+            continue;
+        };
+
+        if has_destination || has_move_to || has_attack_order {
+            continue;
+        }
+
+        match order_queue.0.pop_front() {
+            Some(waypoint) => {
+                cmds.entity(unit_ent)
+                    .insert((pf_comps::Destination, PathTo(waypoint)));
+            }
+            None => {
+                cmds.entity(unit_ent).remove::<OrderQueue>();
+            }
+        }
+    }
+}
+
+// the route of every selected unit, through its current goal and each of its queued waypoints
+fn draw_order_queues(
+    mut gizmos: Gizmos,
+    q_unit: Query<
+        (
+            &Transform,
+            &OrderQueue,
+            &Team,
+            Option<&MoveTo>,
+            Option<&PathTo>,
+            Option<&MoveGoal>,
+            Has<pf_comps::Destination>,
+        ),
+        With<SelectedUnit>,
+    >,
+) {
+    for (trans, order_queue, team, move_to, path_to, move_goal, has_destination) in q_unit.iter() {
+        let flow_goal = path_to
+            .map(|path_to| path_to.0)
+            .or(move_goal.map(|move_goal| move_goal.0))
+            .filter(|_| has_destination);
+        let goal = move_to.map(|move_to| move_to.0).or(flow_goal);
+
+        let route = std::iter::once(trans.translation)
+            .chain(goal)
+            .chain(order_queue.0.iter().copied())
+            .map(|point| point.with_y(0.5));
+
+        gizmos.linestrip(route, team.color());
+    }
 }
 
 fn set_is_moving(mut q_is_moving: Query<(&mut IsMoving, &Velocity), With<UnitType>>) {
@@ -132,42 +349,86 @@ fn move_unit(
             &pf_comps::Boid,
             &Speed,
             &mut Velocity,
+            Option<&FormationSlot>,
         ),
-        With<pf_comps::Destination>,
+        (With<pf_comps::Destination>, Without<PathTo>),
     >,
     time: Res<Time>,
 ) {
     let dt = time.delta_secs();
-    let rotation_speed = 5.0; // radians/sec
 
+    // units with a goal of their own are steered by the goal fields instead
     for ff in q_ff.iter() {
-        for (ent, mut tx, _boid, speed, mut vel) in q_units.iter_mut() {
+        for (ent, mut tx, _boid, speed, mut vel, slot) in q_units.iter_mut() {
             if let Some(steering) = ff.steering_map.get(&ent) {
-                // ——— 1) Rotate toward steering ———
-                if steering.length_squared() > 1e-6 {
-                    // Compute the yaw so that “forward” (-Z) points along steering
-                    let target_yaw = f32::atan2(-steering.x, -steering.z);
-                    let target_rot = Quat::from_rotation_y(target_yaw);
-
-                    // Slerp current rotation → target
-                    let min_rotation = 0.1;
-                    let angle_diff = tx.rotation.angle_between(target_rot);
-                    if angle_diff > min_rotation {
-                        let max_step = rotation_speed * dt;
-                        let t = (max_step.min(angle_diff)) / angle_diff;
-                        tx.rotation = tx.rotation.slerp(target_rot, t);
-                    } else {
-                        let t = (rotation_speed * dt).clamp(0.0, 1.0);
-                        tx.rotation = tx.rotation.slerp(target_rot, t);
-                    }
-
-                    // ——— 2) Drive velocity along steering ———
-                    vel.linvel = steering.normalize() * speed.0;
-                } else {
-                    // No steering → stop
-                    vel.linvel = Vec3::ZERO;
-                }
+                let speed = slot.map_or(speed.0, |slot| slot.speed);
+                steer_towards(&mut tx, &mut vel, *steering, speed, dt);
             }
         }
     }
 }
+
+fn move_to_point(
+    mut cmds: Commands,
+    mut q_units: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Velocity,
+            &Speed,
+            &MoveTo,
+            Option<&FormationSlot>,
+        ),
+        Without<pf_comps::Destination>,
+    >,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+    let arrive_radius = 5.0;
+
+    for (ent, mut tx, mut vel, speed, move_to, slot) in q_units.iter_mut() {
+        let mut steering = move_to.0 - tx.translation;
+        steering.y = 0.0;
+
+        if steering.length() <= arrive_radius {
+            vel.linvel = Vec3::ZERO;
+            cmds.entity(ent).remove::<MoveTo>();
+            continue;
+        }
+
+        // a group in formation keeps to the pace of its slowest unit
+        let speed = slot.map_or(speed.0, |slot| slot.speed);
+        steer_towards(&mut tx, &mut vel, steering, speed, dt);
+    }
+}
+
+// rotates a unit so "forward" (-Z) points along the steering direction, and drives it that way
+pub fn steer_towards(tx: &mut Transform, vel: &mut Velocity, steering: Vec3, speed: f32, dt: f32) {
+    let rotation_speed = 5.0; // radians/sec
+
+    // No steering → stop
+    if steering.length_squared() <= 1e-6 {
+        vel.linvel = Vec3::ZERO;
+        return;
+    }
+
+    // ——— 1) Rotate toward steering ———
+    // Compute the yaw so that “forward” (-Z) points along steering
+    let target_yaw = f32::atan2(-steering.x, -steering.z);
+    let target_rot = Quat::from_rotation_y(target_yaw);
+
+    // Slerp current rotation → target
+    let min_rotation = 0.1;
+    let angle_diff = tx.rotation.angle_between(target_rot);
+    if angle_diff > min_rotation {
+        let max_step = rotation_speed * dt;
+        let t = (max_step.min(angle_diff)) / angle_diff;
+        tx.rotation = tx.rotation.slerp(target_rot, t);
+    } else {
+        let t = (rotation_speed * dt).clamp(0.0, 1.0);
+        tx.rotation = tx.rotation.slerp(target_rot, t);
+    }
+
+    // ——— 2) Drive velocity along steering ———
+    vel.linvel = steering.normalize() * speed;
+}
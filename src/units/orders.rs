@@ -0,0 +1,399 @@
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_timer;
+use bevy_rapier3d::plugin::ReadRapierContext;
+use bevy_rapier3d::prelude::Velocity;
+use bevy_rts_camera::RtsCamera;
+use bevy_rts_pathfinding::components as pf_comps;
+use bevy_rts_pathfinding::events as pf_events;
+use std::time::Duration;
+use strum::IntoEnumIterator;
+
+use super::components::*;
+use crate::asset_manager::audio::{most_common_unit, AudioCmd, UnitAudioEv};
+use crate::combat::components::{AttackOrder, Health, Target, Weapon};
+use crate::events::{AttackMoveEv, GuardEv, PatrolEv, UnitOrderEv};
+use crate::fog_of_war::FogOfWar;
+use crate::game_state::GameState;
+use crate::resources::{GameCommands, MouseCoords};
+use crate::structures::components::Structure;
+use crate::teams::Team;
+use crate::utils;
+
+const GUARD_RADIUS: f32 = 80.0; // enemies this close to the guarded entity get attacked
+const GUARD_LEASH: f32 = 120.0; // guards give up the chase once the enemy is this far from it
+const GUARD_FOLLOW_DIST: f32 = 25.0;
+const ATTACK_MOVE_ARRIVE: f32 = 10.0;
+
+// everything a unit could be busy with, cleared whenever it's given a new order
+type OrderState = (
+    pf_comps::Destination,
+    MoveGoal,
+    MoveTo,
+    PathTo,
+    OrderQueue,
+    AttackOrder,
+    Target,
+    HoldPosition,
+    Patrol,
+    Guard,
+    FormationSlot,
+    AttackMove,
+);
+
+pub struct OrdersPlugin;
+
+impl Plugin for OrdersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                order_hotkeys,
+                advance_patrols,
+                guard_units.run_if(on_timer(Duration::from_millis(250))),
+                engage_on_attack_move.run_if(on_timer(Duration::from_millis(250))),
+                resume_attack_moves,
+            )
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_observer(issue_order)
+        .add_observer(start_patrol)
+        .add_observer(start_guard)
+        .add_observer(start_attack_move);
+    }
+}
+
+fn order_hotkeys(
+    mut cmds: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    game_cmds: Res<GameCommands>,
+) {
+    if !game_cmds.is_any_unit_selected {
+        return;
+    }
+
+    // ctrl and shift combos belong to the selection and the control groups
+    let modifiers = [
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::ShiftLeft,
+        KeyCode::ShiftRight,
+    ];
+    if keys.any_pressed(modifiers) {
+        return;
+    }
+
+    if let Some(order) = UnitOrder::iter().find(|order| keys.just_pressed(order.key())) {
+        cmds.trigger(UnitOrderEv(order));
+    }
+}
+
+fn order_audio(order: UnitOrder) -> AudioCmd {
+    match order {
+        UnitOrder::Stop => AudioCmd::Stop,
+        UnitOrder::HoldPosition => AudioCmd::Hold,
+        UnitOrder::Patrol => AudioCmd::Patrol,
+        UnitOrder::Guard => AudioCmd::Guard,
+        UnitOrder::AttackMove => AudioCmd::AttackMove,
+    }
+}
+
+fn issue_order(
+    trigger: Trigger<UnitOrderEv>,
+    mut cmds: Commands,
+    mut game_cmds: ResMut<GameCommands>,
+    mut q_unit: Query<(Entity, &UnitType, &mut Velocity), With<SelectedUnit>>,
+) {
+    let order = trigger.0;
+
+    if order.needs_target() {
+        game_cmds.pending_order = Some(order);
+        return;
+    }
+
+    for (unit_ent, _, mut vel) in q_unit.iter_mut() {
+        vel.linvel = Vec3::ZERO;
+        cmds.entity(unit_ent).remove::<OrderState>();
+
+        if order == UnitOrder::HoldPosition {
+            cmds.entity(unit_ent).insert(HoldPosition);
+        }
+    }
+
+    if let Some(unit) = most_common_unit(q_unit.iter().map(|(_, unit_type, _)| unit_type)) {
+        cmds.trigger(UnitAudioEv::new(order_audio(order), unit));
+    }
+}
+
+fn start_patrol(
+    trigger: Trigger<PatrolEv>,
+    mut cmds: Commands,
+    q_unit: Query<(Entity, &Transform, &UnitType), With<SelectedUnit>>,
+) {
+    let point = trigger.0;
+
+    for (unit_ent, trans, _) in q_unit.iter() {
+        cmds.entity(unit_ent).remove::<OrderState>().insert((
+            Patrol::new(trans.translation, point),
+            pf_comps::Destination,
+            PathTo(point),
+        ));
+    }
+
+    if let Some(unit) = most_common_unit(q_unit.iter().map(|(_, _, unit_type)| unit_type)) {
+        cmds.trigger(UnitAudioEv::new(AudioCmd::Patrol, unit));
+    }
+}
+
+fn start_guard(
+    trigger: Trigger<GuardEv>,
+    mut cmds: Commands,
+    q_unit: Query<(Entity, &UnitType), With<SelectedUnit>>,
+) {
+    let guarded_ent = trigger.0;
+
+    // a unit can't guard itself
+    let guards: Vec<(Entity, &UnitType)> = q_unit
+        .iter()
+        .filter(|(unit_ent, _)| *unit_ent != guarded_ent)
+        .collect();
+
+    for (unit_ent, _) in guards.iter() {
+        cmds.entity(*unit_ent)
+            .remove::<OrderState>()
+            .insert(Guard(guarded_ent));
+    }
+
+    if let Some(unit) = most_common_unit(guards.iter().map(|(_, unit_type)| *unit_type)) {
+        cmds.trigger(UnitAudioEv::new(AudioCmd::Guard, unit));
+    }
+}
+
+// each time a patrolling unit reaches one end, it heads back to the other
+fn advance_patrols(
+    mut cmds: Commands,
+    mut removed_destination: RemovedComponents<pf_comps::Destination>,
+    mut q_unit: Query<(&mut Patrol, Has<pf_comps::Destination>)>,
+) {
+    for unit_ent in removed_destination.read() {
+        let Ok((mut patrol, has_destination)) = q_unit.get_mut(unit_ent) else {
+            continue;
+        };
+
+        if has_destination {
+            continue;
+        }
+
+        let point = patrol.turn_around();
+        cmds.entity(unit_ent)
+            .insert((pf_comps::Destination, PathTo(point)));
+    }
+}
+
+// guards stay close to what they're guarding, and go after any enemy their team can see come
+// near it
+fn guard_units(
+    mut cmds: Commands,
+    fog: Res<FogOfWar>,
+    q_guard: Query<(
+        Entity,
+        &Transform,
+        &Team,
+        &UnitType,
+        &Guard,
+        Option<&AttackOrder>,
+    )>,
+    q_trans: Query<&Transform>,
+    q_enemy: Query<(Entity, &Transform, &Team, &Health), Or<(With<Unit>, With<Structure>)>>,
+) {
+    for (unit_ent, trans, team, unit_type, guard, attack_order) in q_guard.iter() {
+        // what was being guarded is gone
+        let Ok(guarded_trans) = q_trans.get(guard.0) else {
+            cmds.entity(unit_ent).remove::<Guard>();
+            continue;
+        };
+
+        let guarded_pos = guarded_trans.translation;
+
+        // don't get drawn away chasing an enemy that has moved off
+        if let Some(attack_order) = attack_order {
+            let is_too_far = q_trans.get(attack_order.0).ok().is_none_or(|target_trans| {
+                target_trans.translation.distance(guarded_pos) > GUARD_LEASH
+            });
+
+            if is_too_far {
+                cmds.entity(unit_ent)
+                    .try_remove::<(AttackOrder, Target, pf_comps::Destination)>();
+            }
+            continue;
+        }
+
+        let threat = q_enemy
+            .iter()
+            .filter(|(_, enemy_trans, enemy_team, health)| {
+                team.is_enemy(enemy_team)
+                    && !health.is_dead()
+                    && enemy_trans.translation.distance(guarded_pos) <= GUARD_RADIUS
+                    && fog.is_visible(team, enemy_trans.translation)
+            })
+            .min_by(|(_, a, _, _), (_, b, _, _)| {
+                let a = a.translation.distance_squared(guarded_pos);
+                let b = b.translation.distance_squared(guarded_pos);
+                a.total_cmp(&b)
+            })
+            .map(|(enemy_ent, _, _, _)| enemy_ent);
+
+        if let Some(threat) = threat.filter(|_| unit_type.is_armed()) {
+            cmds.entity(unit_ent)
+                .remove::<(pf_comps::Destination, PathTo)>()
+                .insert((AttackOrder(threat), Target(threat)));
+            continue;
+        }
+
+        let offset = trans.translation - guarded_pos;
+        if offset.xz().length() > GUARD_FOLLOW_DIST {
+            let point = guarded_pos + offset.normalize_or_zero() * GUARD_FOLLOW_DIST / 2.0;
+            cmds.entity(unit_ent)
+                .insert((pf_comps::Destination, PathTo(point)));
+        }
+    }
+}
+
+fn start_attack_move(
+    _trigger: Trigger<AttackMoveEv>,
+    mut cmds: Commands,
+    mouse_coords: Res<MouseCoords>,
+    q_map_base: Query<&GlobalTransform, With<pf_comps::MapBase>>,
+    q_cam: Query<(&Camera, &GlobalTransform), With<RtsCamera>>,
+    q_unit: Query<(Entity, &UnitType), With<SelectedUnit>>,
+) {
+    let Ok(map_base) = q_map_base.single() else {
+        return;
+    };
+    let Ok((cam, cam_trans)) = q_cam.single() else {
+        return;
+    };
+
+    // same conversion as the select box corners
+    let Some(dest) = utils::get_world_coords(map_base, cam_trans, cam, mouse_coords.viewport)
+    else {
+        return;
+    };
+
+    let mut units = Vec::new();
+    for (unit_ent, unit_type) in q_unit.iter() {
+        cmds.entity(unit_ent)
+            .remove::<OrderState>()
+            .insert(pf_comps::Destination);
+
+        // unarmed units have nothing to engage with, it's a plain move for them
+        if unit_type.is_armed() {
+            cmds.entity(unit_ent).insert(AttackMove(dest));
+        }
+
+        units.push(unit_ent);
+    }
+
+    if units.is_empty() {
+        return;
+    }
+
+    cmds.trigger(pf_events::InitializeFlowFieldEv(units));
+
+    if let Some(unit) = most_common_unit(q_unit.iter().map(|(_, unit_type)| unit_type)) {
+        cmds.trigger(UnitAudioEv::new(AudioCmd::AttackMove, unit));
+    }
+}
+
+// attack-moving units break off to fight whatever enemy they come across, as long as their
+// team can see it
+fn engage_on_attack_move(
+    mut cmds: Commands,
+    read_rapier: ReadRapierContext,
+    fog: Res<FogOfWar>,
+    q_unit: Query<
+        (Entity, &Transform, &Team, &UnitType, &Weapon),
+        (With<AttackMove>, Without<AttackOrder>),
+    >,
+    q_other: Query<(&Transform, &Team, &Health)>,
+) {
+    let Ok(rapier_ctx) = read_rapier.single() else {
+        return;
+    };
+
+    for (unit_ent, trans, team, unit_type, weapon) in q_unit.iter() {
+        let pos = trans.translation;
+        let radius = weapon.range.max(unit_type.sight());
+
+        let nearest = utils::overlap_sphere(&rapier_ctx, pos, radius)
+            .into_iter()
+            .filter_map(|other_ent| {
+                let (other_trans, other_team, health) = q_other.get(other_ent).ok()?;
+                if !team.is_enemy(other_team) || health.is_dead() {
+                    return None;
+                }
+
+                if !fog.is_visible(team, other_trans.translation) {
+                    return None;
+                }
+
+                Some((other_ent, pos.distance_squared(other_trans.translation)))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+
+        if let Some((enemy_ent, _)) = nearest {
+            cmds.entity(unit_ent)
+                .remove::<(pf_comps::Destination, MoveTo, PathTo)>()
+                .insert((AttackOrder(enemy_ent), Target(enemy_ent)));
+        }
+    }
+}
+
+// once a fight is over, units path on to where they were going. a move only runs out at its
+// destination, so that ends the attack move
+fn resume_attack_moves(
+    mut cmds: Commands,
+    mut removed_destination: RemovedComponents<pf_comps::Destination>,
+    mut removed_attack_order: RemovedComponents<AttackOrder>,
+    q_unit: Query<(
+        &Transform,
+        &AttackMove,
+        Has<pf_comps::Destination>,
+        Has<AttackOrder>,
+    )>,
+) {
+    let fought: Vec<Entity> = removed_attack_order.read().collect();
+    let arrived: Vec<Entity> = removed_destination
+        .read()
+        .filter(|unit_ent| !fought.contains(unit_ent))
+        .collect();
+
+    for unit_ent in arrived {
+        let Ok((_, _, has_destination, has_attack_order)) = q_unit.get(unit_ent) else {
+            continue;
+        };
+
+        if !has_destination && !has_attack_order {
+            cmds.entity(unit_ent).remove::<AttackMove>();
+        }
+    }
+
+    for unit_ent in fought {
+        let Ok((trans, attack_move, has_destination, has_attack_order)) = q_unit.get(unit_ent)
+        else {
+            continue;
+        };
+
+        if has_destination || has_attack_order {
+            continue;
+        }
+
+        if trans.translation.xz().distance(attack_move.0.xz()) <= ATTACK_MOVE_ARRIVE {
+            cmds.entity(unit_ent).remove::<AttackMove>();
+            continue;
+        }
+
+        // units that finish their fights together share the goal's field
+        cmds.entity(unit_ent)
+            .insert((pf_comps::Destination, PathTo(attack_move.0)));
+    }
+}
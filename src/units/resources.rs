@@ -4,7 +4,8 @@ pub struct ResourcesPlugin;
 
 impl Plugin for ResourcesPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<UnlockedUnits>();
+        app.init_resource::<UnlockedUnits>()
+            .init_resource::<Formation>();
     }
 }
 
@@ -12,6 +13,74 @@ impl Plugin for ResourcesPlugin {
 pub struct UnlockedUnits {
     pub rifleman: bool,  // barracks built
     pub tank_gen1: bool, // vehicle depot built
-    pub tank_gen2: bool, // vehicle depot build, (eventually research as well)
+    pub tank_gen2: bool, // vehicle depot built and researched
     pub artillery: bool, // vehicle depot build,
+    pub harvester: bool, // vehicle depot and refinery built
+}
+
+// how group move orders are laid out, F (or the button under the orders) cycles through them
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Formation {
+    #[default]
+    Box,
+    Line,
+    Wedge,
+}
+
+impl Formation {
+    pub fn next(&self) -> Self {
+        match self {
+            Formation::Box => Formation::Line,
+            Formation::Line => Formation::Wedge,
+            Formation::Wedge => Formation::Box,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            Formation::Box => "Box (F)",
+            Formation::Line => "Line (F)",
+            Formation::Wedge => "Wedge (F)",
+        }
+    }
+
+    // slot offsets for n units, in slot widths, row by row from the front. x is to the right
+    // of the facing direction and y is forwards, with the group centered on the origin
+    pub fn rows(&self, n: usize) -> Vec<Vec<Vec2>> {
+        let centered = |count: usize, y: f32| -> Vec<Vec2> {
+            (0..count)
+                .map(|i| Vec2::new(i as f32 - (count - 1) as f32 / 2.0, y))
+                .collect()
+        };
+
+        match self {
+            Formation::Line => vec![centered(n, 0.0)],
+            Formation::Box => {
+                let cols = (n as f32).sqrt().ceil() as usize;
+                let rows = n.div_ceil(cols);
+                let front = (rows - 1) as f32 / 2.0;
+
+                (0..rows)
+                    .map(|row| centered(cols.min(n - row * cols), front - row as f32))
+                    .collect()
+            }
+            // one unit at the tip, then a unit further out on each side for every row back
+            Formation::Wedge => {
+                let rows = 1 + n / 2;
+                let front = (rows - 1) as f32 / 2.0;
+
+                (0..rows)
+                    .map(|row| {
+                        let y = front - row as f32;
+                        let x = row as f32;
+                        match row {
+                            0 => vec![Vec2::new(0.0, y)],
+                            _ if row * 2 > n - 1 => vec![Vec2::new(-x, y)],
+                            _ => vec![Vec2::new(-x, y), Vec2::new(x, y)],
+                        }
+                    })
+                    .collect()
+            }
+        }
+    }
 }
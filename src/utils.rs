@@ -1,7 +1,10 @@
 use std::f32::consts::FRAC_PI_2;
 
 use bevy::prelude::*;
-use bevy_rapier3d::{plugin::RapierContext, prelude::QueryFilter};
+use bevy_rapier3d::{
+    plugin::RapierContext,
+    prelude::{Collider, QueryFilter},
+};
 
 pub fn cast_ray(
     rapier: &RapierContext,
@@ -71,3 +74,17 @@ pub fn billboard_sync(
     style.width = Val::Px(screen_width);
     style.height = Val::Px(screen_height);
 }
+
+// every non-sensor collider overlapping a sphere, used for "what is around me" checks
+pub fn overlap_sphere(rapier: &RapierContext, center: Vec3, radius: f32) -> Vec<Entity> {
+    let shape = Collider::ball(radius);
+    let filter = QueryFilter::default().exclude_sensors();
+
+    let mut hits = Vec::new();
+    rapier.intersections_with_shape(center, Quat::IDENTITY, &shape, filter, |ent| {
+        hits.push(ent);
+        true
+    });
+
+    hits
+}